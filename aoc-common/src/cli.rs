@@ -0,0 +1,161 @@
+use std::env;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process;
+use std::str::FromStr;
+
+use crate::input::read_input;
+
+// Where to read the puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    File(PathBuf),
+    Stdin,
+    // The example from the puzzle text, which each day has built in.
+    Example,
+}
+
+impl InputSource {
+    // "-" means stdin, as usual.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            InputSource::Stdin
+        } else {
+            InputSource::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn read(&self, example: &str) -> Result<String, String> {
+        match self {
+            InputSource::File(path) => {
+                read_input(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))
+            }
+            InputSource::Stdin => {
+                let mut s = String::new();
+                io::stdin()
+                    .read_to_string(&mut s)
+                    .map_err(|e| format!("Failed to read stdin: {}", e))?;
+                Ok(s)
+            }
+            InputSource::Example => Ok(example.to_string()),
+        }
+    }
+}
+
+// A very small command-line parser. Callers pull out the flags they know about, then call
+// [finish] to complain about anything left over.
+pub struct Args(Vec<String>);
+
+impl Args {
+    pub fn new(args: Vec<String>) -> Self {
+        Args(args)
+    }
+
+    pub fn from_env() -> Self {
+        Args(env::args().skip(1).collect())
+    }
+
+    // Is [name] present? e.g. "--example"
+    pub fn flag(&mut self, name: &str) -> bool {
+        let before = self.0.len();
+        self.0.retain(|arg| arg != name);
+        self.0.len() != before
+    }
+
+    // The value following [name], e.g. "--day 13".
+    pub fn value(&mut self, name: &str) -> Result<Option<String>, String> {
+        match self.0.iter().position(|arg| arg == name) {
+            None => Ok(None),
+            Some(i) if i + 1 == self.0.len() => Err(format!("{} needs a value", name)),
+            Some(i) => {
+                let value = self.0.remove(i + 1);
+                self.0.remove(i);
+                Ok(Some(value))
+            }
+        }
+    }
+
+    pub fn parsed_value<T: FromStr>(&mut self, name: &str) -> Result<Option<T>, String> {
+        match self.value(name)? {
+            None => Ok(None),
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("Couldn't understand {} {}", name, value)),
+        }
+    }
+
+    // The next argument that isn't a flag. A lone "-" counts, since it means stdin.
+    pub fn positional(&mut self) -> Option<String> {
+        let i = self
+            .0
+            .iter()
+            .position(|arg| arg == "-" || !arg.starts_with('-'))?;
+        Some(self.0.remove(i))
+    }
+
+    // The input for a day's binary: either a path (or "-" for stdin), or --example.
+    pub fn input_source(&mut self, default_path: &str) -> Result<InputSource, String> {
+        let example = self.flag("--example");
+        match (example, self.positional()) {
+            (true, Some(path)) => Err(format!("Can't pass both --example and {}", path)),
+            (true, None) => Ok(InputSource::Example),
+            (false, Some(path)) => Ok(InputSource::from_arg(&path)),
+            (false, None) => Ok(InputSource::File(PathBuf::from(default_path))),
+        }
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self.0.first() {
+            None => Ok(()),
+            Some(arg) => Err(format!("Unrecognised argument: {}", arg)),
+        }
+    }
+}
+
+pub fn usage_error(usage: &str, e: &str) -> ! {
+    eprintln!("{}\n\n{}", e, usage);
+    process::exit(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Args {
+        Args::new(args.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn flags_and_values_can_come_in_any_order() {
+        let mut a = args(&["run", "--part", "2", "--example", "--day", "13"]);
+        assert_eq!(a.positional(), Some("run".to_string()));
+        assert_eq!(a.parsed_value::<u8>("--day"), Ok(Some(13)));
+        assert_eq!(a.parsed_value::<u8>("--part"), Ok(Some(2)));
+        assert!(a.flag("--example"));
+        assert!(!a.flag("--all"));
+        assert_eq!(a.finish(), Ok(()));
+    }
+
+    #[test]
+    fn leftover_arguments_are_an_error() {
+        let mut a = args(&["--day"]);
+        assert!(a.value("--day").is_err());
+
+        let a = args(&["--bogus"]);
+        assert!(a.finish().is_err());
+    }
+
+    #[test]
+    fn input_source() {
+        let source = |a: &[&str]| args(a).input_source("default");
+        assert_eq!(source(&[]), Ok(InputSource::File(PathBuf::from("default"))));
+        assert_eq!(source(&["-"]), Ok(InputSource::Stdin));
+        assert_eq!(
+            source(&["some/file"]),
+            Ok(InputSource::File(PathBuf::from("some/file")))
+        );
+        assert_eq!(source(&["--example"]), Ok(InputSource::Example));
+        assert!(source(&["--example", "some/file"]).is_err());
+    }
+}
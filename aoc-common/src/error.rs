@@ -0,0 +1,94 @@
+use std::error::Error;
+use std::fmt;
+
+// Something in the input didn't look like we expected. The line and column are 1-indexed,
+// to match what an editor shows.
+//
+// [FromStr] implementations only see a single line, so they fill in the column and leave the
+// line for whoever split up the input to add with [at_line]. Parsers that are built out of
+// smaller parsers should use [shift_columns] to make the column relative to their own input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    // The offending bit of input
+    pub text: String,
+    // What we wanted to see instead, e.g. "an integer"
+    pub expected: String,
+}
+
+impl ParseError {
+    // An error that isn't about any particular position, e.g. something missing from the input.
+    pub fn new(text: impl Into<String>, expected: impl Into<String>) -> Self {
+        ParseError {
+            line: None,
+            column: None,
+            text: text.into(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn at_column(column: usize, text: impl Into<String>, expected: impl Into<String>) -> Self {
+        ParseError {
+            column: Some(column),
+            ..ParseError::new(text, expected)
+        }
+    }
+
+    pub fn at_line(self, line: usize) -> Self {
+        ParseError {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    // The error came from parsing a substring that started [offset] characters into the line.
+    pub fn shift_columns(self, offset: usize) -> Self {
+        ParseError {
+            column: self.column.map(|column| column + offset),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {}, column {}: ", line, column)?,
+            (Some(line), None) => write!(f, "line {}: ", line)?,
+            (None, Some(column)) => write!(f, "column {}: ", column)?,
+            (None, None) => (),
+        }
+
+        if self.text.is_empty() {
+            write!(f, "expected {}, found nothing", self.expected)
+        } else {
+            write!(f, "expected {}, found {:?}", self.expected, self.text)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_includes_whatever_position_we_know() {
+        let error = ParseError::at_column(3, "Q", "X, Y or Z");
+        assert_eq!(
+            error.to_string(),
+            r#"column 3: expected X, Y or Z, found "Q""#
+        );
+
+        let error = error.shift_columns(2).at_line(7);
+        assert_eq!(
+            error.to_string(),
+            r#"line 7, column 5: expected X, Y or Z, found "Q""#
+        );
+
+        let error = ParseError::new("", "exactly one S");
+        assert_eq!(error.to_string(), "expected exactly one S, found nothing");
+    }
+}
@@ -0,0 +1,392 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::grid::{Grid, Pos};
+
+// Shortest paths, for whenever a puzzle turns out to be a graph in disguise.
+//
+// Anything implementing [Graph] can be searched. There are two implementations here: an
+// adjacency matrix for small graphs given as a list of edges (like the valves in day 16), and
+// [GridGraph] for walking around a [Grid] (like the hill in day 12).
+
+pub trait Graph {
+    type Node: Copy + Eq + Hash + Ord;
+
+    // Where we can get to in one step from [node], and what that step costs.
+    fn neighbours(&self, node: Self::Node) -> Vec<(Self::Node, usize)>;
+}
+
+// Nodes are 0..len, and [edges[i][j]] is the weight of the edge from i to j, if there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AdjacencyMatrix {
+    edges: Vec<Vec<Option<usize>>>,
+}
+
+impl AdjacencyMatrix {
+    pub fn new(len: usize) -> Self {
+        AdjacencyMatrix {
+            edges: vec![vec![None; len]; len],
+        }
+    }
+
+    pub fn from_rows(edges: Vec<Vec<Option<usize>>>) -> Self {
+        assert!(
+            edges.iter().all(|row| row.len() == edges.len()),
+            "BUG: adjacency matrix isn't square"
+        );
+        AdjacencyMatrix { edges }
+    }
+
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: usize) {
+        self.edges[from][to] = Some(weight);
+    }
+
+    pub fn weight(&self, from: usize, to: usize) -> Option<usize> {
+        self.edges[from][to]
+    }
+
+    pub fn row(&self, from: usize) -> &[Option<usize>] {
+        &self.edges[from]
+    }
+
+    // Shortest paths between every pair of nodes, in O(n^3). Every node is 0 away from itself,
+    // even if there's an edge (or a longer cycle) back to it.
+    pub fn floyd_warshall(&self) -> AllPairs {
+        let n = self.len();
+        let mut distances = self.edges.clone();
+        // [next[i][j]] is the first step on the best path we've found from i to j.
+        let mut next: Vec<Vec<Option<usize>>> = (0..n)
+            .map(|i| (0..n).map(|j| distances[i][j].map(|_| j)).collect())
+            .collect();
+        for i in 0..n {
+            distances[i][i] = Some(0);
+            next[i][i] = Some(i);
+        }
+
+        for via in 0..n {
+            // At this point, we've got the best paths that only go through vertices 0..via.
+            for i in 0..n {
+                for j in 0..n {
+                    if let (Some(x1), Some(x2)) = (distances[i][via], distances[via][j]) {
+                        if distances[i][j].is_none_or(|current| x1 + x2 < current) {
+                            distances[i][j] = Some(x1 + x2);
+                            next[i][j] = next[i][via];
+                        }
+                    }
+                }
+            }
+        }
+
+        AllPairs { distances, next }
+    }
+}
+
+impl Graph for AdjacencyMatrix {
+    type Node = usize;
+
+    fn neighbours(&self, node: usize) -> Vec<(usize, usize)> {
+        self.edges[node]
+            .iter()
+            .enumerate()
+            .filter_map(|(j, weight)| weight.map(|weight| (j, weight)))
+            .collect()
+    }
+}
+
+// The output of [floyd_warshall].
+#[derive(Debug, Clone)]
+pub struct AllPairs {
+    distances: Vec<Vec<Option<usize>>>,
+    next: Vec<Vec<Option<usize>>>,
+}
+
+impl AllPairs {
+    pub fn distance(&self, from: usize, to: usize) -> Option<usize> {
+        self.distances[from][to]
+    }
+
+    // The nodes along a shortest path, including both ends.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut path = vec![from];
+        let mut current = from;
+        while current != to {
+            current = self.next[current][to]?;
+            path.push(current);
+        }
+        Some(path)
+    }
+}
+
+// Moving around a grid, one square up/down/left/right at a time. [can_move] gets the values of
+// the square we're on and the one we want to move to.
+pub struct GridGraph<'a, T, F> {
+    grid: &'a Grid<T>,
+    can_move: F,
+}
+
+impl<'a, T, F: Fn(&T, &T) -> bool> GridGraph<'a, T, F> {
+    pub fn new(grid: &'a Grid<T>, can_move: F) -> Self {
+        GridGraph { grid, can_move }
+    }
+}
+
+impl<T, F: Fn(&T, &T) -> bool> Graph for GridGraph<'_, T, F> {
+    type Node = Pos;
+
+    fn neighbours(&self, pos: Pos) -> Vec<(Pos, usize)> {
+        self.grid
+            .neighbours4(pos)
+            .filter(|next| (self.can_move)(&self.grid[pos], &self.grid[*next]))
+            .map(|next| (next, 1))
+            .collect()
+    }
+}
+
+// Everywhere a search reached, how far away it was, and how we got there.
+#[derive(Debug, Clone)]
+pub struct Paths<N> {
+    distances: HashMap<N, usize>,
+    previous: HashMap<N, N>,
+}
+
+impl<N: Copy + Eq + Hash> Paths<N> {
+    fn new() -> Self {
+        Paths {
+            distances: HashMap::new(),
+            previous: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: N) -> Option<usize> {
+        self.distances.get(&node).copied()
+    }
+
+    // The nodes along the path from (one of) the start(s) to [node], including both ends.
+    pub fn path_to(&self, node: N) -> Option<Vec<N>> {
+        self.distances.get(&node)?;
+
+        let mut path = vec![node];
+        let mut current = node;
+        while let Some(previous) = self.previous.get(&current) {
+            path.push(*previous);
+            current = *previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Every node we reached, and its distance.
+    pub fn reached(&self) -> impl Iterator<Item = (N, usize)> + '_ {
+        self.distances
+            .iter()
+            .map(|(node, distance)| (*node, *distance))
+    }
+}
+
+// Counts steps, ignoring the weights.
+pub fn bfs<G: Graph>(graph: &G, start: G::Node) -> Paths<G::Node> {
+    multi_source_bfs(graph, [start])
+}
+
+// The distance to each node from whichever start is closest.
+pub fn multi_source_bfs<G: Graph>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> Paths<G::Node> {
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if paths.distances.insert(start, 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        let distance = paths.distances[&node];
+        for (next, _) in graph.neighbours(node) {
+            if let Entry::Vacant(entry) = paths.distances.entry(next) {
+                entry.insert(distance + 1);
+                paths.previous.insert(next, node);
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+pub fn dijkstra<G: Graph>(graph: &G, start: G::Node) -> Paths<G::Node> {
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+    paths.distances.insert(start, 0);
+    queue.push(Reverse((0, start)));
+
+    while let Some(Reverse((distance, node))) = queue.pop() {
+        if distance > paths.distances[&node] {
+            // We've already found a shorter way here.
+            continue;
+        }
+        for (next, weight) in graph.neighbours(node) {
+            let via_node = distance + weight;
+            if paths.distance(next).is_none_or(|best| via_node < best) {
+                paths.distances.insert(next, via_node);
+                paths.previous.insert(next, node);
+                queue.push(Reverse((via_node, next)));
+            }
+        }
+    }
+    paths
+}
+
+// Dijkstra, but heading for a particular [goal]. [heuristic] has to be a lower bound on the
+// distance to the goal, or the answer might not be the shortest. Returns the distance and the
+// path.
+pub fn astar<G: Graph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+    heuristic: impl Fn(G::Node) -> usize,
+) -> Option<(usize, Vec<G::Node>)> {
+    let mut paths = Paths::new();
+    let mut queue = BinaryHeap::new();
+    paths.distances.insert(start, 0);
+    queue.push(Reverse((heuristic(start), start)));
+
+    while let Some(Reverse((_, node))) = queue.pop() {
+        if node == goal {
+            return Some((paths.distances[&goal], paths.path_to(goal)?));
+        }
+        let distance = paths.distances[&node];
+        for (next, weight) in graph.neighbours(node) {
+            let via_node = distance + weight;
+            if paths.distance(next).is_none_or(|best| via_node < best) {
+                paths.distances.insert(next, via_node);
+                paths.previous.insert(next, node);
+                queue.push(Reverse((via_node + heuristic(next), next)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 3 is two steps but costs 11, 0 -> 2 -> 4 -> 3 is three steps but costs 3.
+    fn weighted() -> AdjacencyMatrix {
+        let mut graph = AdjacencyMatrix::new(6);
+        for (from, to, weight) in [(0, 1, 1), (1, 3, 10), (0, 2, 1), (2, 4, 1), (4, 3, 1)] {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    // '#' is a wall.
+    fn maze() -> Grid<char> {
+        Grid::from_char_map(
+            "\
+.....
+.###.
+...#.
+##.#.
+.....
+",
+        )
+        .unwrap()
+    }
+
+    fn maze_graph(grid: &Grid<char>) -> GridGraph<'_, char, impl Fn(&char, &char) -> bool> {
+        GridGraph::new(grid, |_, to| *to != '#')
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let paths = bfs(&weighted(), 0);
+        assert_eq!(paths.distance(3), Some(2));
+        assert_eq!(paths.path_to(3), Some(vec![0, 1, 3]));
+        assert_eq!(paths.distance(0), Some(0));
+        assert_eq!(paths.path_to(0), Some(vec![0]));
+        // Nothing leads to 5.
+        assert_eq!(paths.distance(5), None);
+        assert_eq!(paths.path_to(5), None);
+        assert_eq!(paths.reached().count(), 5);
+    }
+
+    #[test]
+    fn bfs_on_a_grid() {
+        let grid = maze();
+        let paths = bfs(&maze_graph(&grid), (0, 0));
+        assert_eq!(paths.distance((4, 0)), Some(8));
+        assert_eq!(paths.distance((1, 1)), None);
+
+        let path = paths.path_to((4, 0)).unwrap();
+        assert_eq!(path.len(), 9);
+        assert!(path.iter().all(|pos| grid[*pos] != '#'));
+    }
+
+    #[test]
+    fn multi_source() {
+        let grid = maze();
+        let paths = multi_source_bfs(&maze_graph(&grid), [(0, 0), (4, 4)]);
+        assert_eq!(paths.distance((4, 0)), Some(4));
+        assert_eq!(paths.distance((0, 4)), Some(4));
+        assert_eq!(paths.distance((2, 2)), Some(4));
+        assert_eq!(paths.path_to((4, 2)).unwrap()[0], (4, 4));
+    }
+
+    #[test]
+    fn dijkstra_uses_weights() {
+        let paths = dijkstra(&weighted(), 0);
+        assert_eq!(paths.distance(3), Some(3));
+        assert_eq!(paths.path_to(3), Some(vec![0, 2, 4, 3]));
+        assert_eq!(paths.distance(1), Some(1));
+        assert_eq!(paths.distance(5), None);
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        assert_eq!(astar(&weighted(), 0, 3, |_| 0), Some((3, vec![0, 2, 4, 3])));
+        assert_eq!(astar(&weighted(), 0, 5, |_| 0), None);
+
+        let grid = maze();
+        let graph = maze_graph(&grid);
+        let goal = (4, 0);
+        let manhattan = |(row, column): Pos| row.abs_diff(goal.0) + column.abs_diff(goal.1);
+        let (distance, path) = astar(&graph, (0, 0), goal, manhattan).unwrap();
+        assert_eq!(distance, 8);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(dijkstra(&graph, (0, 0)).distance(goal), Some(distance));
+    }
+
+    #[test]
+    fn floyd_warshall() {
+        let all_pairs = weighted().floyd_warshall();
+        assert_eq!(all_pairs.distance(0, 3), Some(3));
+        assert_eq!(all_pairs.path(0, 3), Some(vec![0, 2, 4, 3]));
+        assert_eq!(all_pairs.distance(2, 3), Some(2));
+        assert_eq!(all_pairs.distance(3, 3), Some(0));
+        assert_eq!(all_pairs.path(4, 4), Some(vec![4]));
+        assert_eq!(all_pairs.distance(3, 0), None);
+        assert_eq!(all_pairs.path(3, 0), None);
+
+        // Same answers as running Dijkstra from everywhere.
+        let graph = weighted();
+        for from in 0..graph.len() {
+            let paths = dijkstra(&graph, from);
+            for to in 0..graph.len() {
+                assert_eq!(all_pairs.distance(from, to), paths.distance(to));
+            }
+        }
+    }
+}
@@ -0,0 +1,606 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::input::numbered_lines;
+use crate::point::{Coord, Point2};
+use crate::ParseError;
+
+// 2D grids, since about every other puzzle has one. Positions are (row, column), with row 0 at
+// the top, which is the order you read a character map in.
+//
+// [Grid] is the dense one, for puzzles that hand you the whole map. [SparseGrid] is for when the
+// interesting cells are scattered over a big (or unbounded) area, like the sand in day 14. It has
+// the same methods as [Grid] wherever they make sense without edges, with (i64, i64) positions.
+
+pub type Pos = (usize, usize);
+
+// Offsets as (row, column), clockwise from up.
+pub const NEIGHBOURS4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+pub const NEIGHBOURS8: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    // Row-major
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Pos) -> T) -> Self {
+        let mut cells = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for column in 0..width {
+                cells.push(f((row, column)));
+            }
+        }
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(width, height, |_| fill.clone())
+    }
+
+    // Every row has to be the same length. If one isn't, the error's line is the (1-indexed)
+    // row number.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self, ParseError> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+
+        let mut cells = Vec::with_capacity(width * height);
+        for (row_number, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(ragged_row_error(width, row.len()).at_line(row_number + 1));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    // Parse a character map, one row per line (blank lines are skipped). [parse] says what each
+    // character means, or None if it doesn't make sense, in which case we complain that we
+    // wanted [expected] instead.
+    pub fn parse_with(
+        s: &str,
+        parse: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let lines = numbered_lines(s);
+        let width = match lines.first() {
+            None => return Err(ParseError::new("", "at least one row")),
+            Some((_, line)) => line.chars().count(),
+        };
+
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for (line_number, line) in &lines {
+            let length = line.chars().count();
+            if length != width {
+                return Err(ragged_row_error(width, length).at_line(*line_number));
+            }
+            for (i, c) in line.chars().enumerate() {
+                let cell = parse(c).ok_or_else(|| {
+                    ParseError::at_column(i + 1, c, expected).at_line(*line_number)
+                })?;
+                cells.push(cell);
+            }
+        }
+        Ok(Grid {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, column): Pos) -> bool {
+        row < self.height && column < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    // The same, for a point whose x is the column and y is the row.
+    pub fn get_point<C: Coord>(&self, p: Point2<C>) -> Option<&T>
+    where
+        usize: TryFrom<C>,
+    {
+        self.get(p.grid_index(self.width, self.height)?)
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    // Where we'd end up moving one step by [offset] from [pos], if that's still on the grid.
+    pub fn step(&self, (row, column): Pos, (d_row, d_column): (isize, isize)) -> Option<Pos> {
+        let row = row.checked_add_signed(d_row)?;
+        let column = column.checked_add_signed(d_column)?;
+        Some((row, column)).filter(|pos| self.contains(*pos))
+    }
+
+    // All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    // Up, right, down and left, skipping any that fall off the edge.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .filter_map(move |offset| self.step(pos, *offset))
+    }
+
+    // Same again, but including diagonals.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .filter_map(move |offset| self.step(pos, *offset))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "BUG: column {} off the grid", column);
+        self.cells.iter().skip(column).step_by(self.width)
+    }
+
+    // The positions you pass moving from [pos] in the direction of [offset], not including [pos]
+    // itself, until you fall off the edge.
+    pub fn ray(&self, pos: Pos, offset: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.step(pos, offset), move |pos| self.step(*pos, offset))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // Flip along the top-left to bottom-right diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |(row, column)| {
+            self[(column, row)].clone()
+        })
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |(row, column)| {
+            self[(self.height - 1 - column, row)].clone()
+        })
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.height, self.width, |(row, column)| {
+            self[(column, self.width - 1 - row)].clone()
+        })
+    }
+
+    // One line per row, with [f] choosing the character for each cell.
+    pub fn render(&self, f: impl Fn(&T) -> char) -> String {
+        let mut s = String::new();
+        for row in self.rows() {
+            s.extend(row.iter().map(&f));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl Grid<char> {
+    pub fn from_char_map(s: &str) -> Result<Self, ParseError> {
+        Grid::parse_with(s, Some, "any character")
+    }
+}
+
+fn ragged_row_error(width: usize, length: usize) -> ParseError {
+    let expected = format!("{} cells, like the first row", width);
+    ParseError::new(format!("{} cells", length), expected)
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("BUG: {:?} is off the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("BUG: {:?} is off the grid", pos))
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|c| *c))
+    }
+}
+
+// A grid that only stores the cells that have something in them. There are no edges, so
+// positions can be negative, and neighbours are never filtered out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<(i64, i64), T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        SparseGrid::default()
+    }
+
+    pub fn get(&self, pos: (i64, i64)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    pub fn get_mut(&mut self, pos: (i64, i64)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    pub fn contains(&self, pos: (i64, i64)) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    // Returns whatever was there before.
+    pub fn insert(&mut self, pos: (i64, i64), value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    pub fn remove(&mut self, pos: (i64, i64)) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // In no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.cells.iter().map(|(pos, value)| (*pos, value))
+    }
+
+    // The smallest (row, column) and largest (row, column) of anything in the grid.
+    pub fn bounds(&self) -> Option<((i64, i64), (i64, i64))> {
+        let rows = || self.cells.keys().map(|pos| pos.0);
+        let columns = || self.cells.keys().map(|pos| pos.1);
+        Some((
+            (rows().min()?, columns().min()?),
+            (rows().max()?, columns().max()?),
+        ))
+    }
+
+    // The cells in [row] that have something in them, as (column, value), left to right.
+    pub fn row(&self, row: i64) -> impl Iterator<Item = (i64, &T)> {
+        let mut cells: Vec<(i64, &T)> = self
+            .iter()
+            .filter(|(pos, _)| pos.0 == row)
+            .map(|(pos, value)| (pos.1, value))
+            .collect();
+        cells.sort_by_key(|(column, _)| *column);
+        cells.into_iter()
+    }
+
+    // Every row with something in it, top to bottom, along with its cells like [row] gives them.
+    pub fn rows(&self) -> impl Iterator<Item = (i64, Vec<(i64, &T)>)> {
+        let mut rows: BTreeMap<i64, Vec<(i64, &T)>> = BTreeMap::new();
+        for ((row, column), value) in self.iter() {
+            rows.entry(row).or_default().push((column, value));
+        }
+        rows.into_iter().map(|(row, mut cells)| {
+            cells.sort_by_key(|(column, _)| *column);
+            (row, cells)
+        })
+    }
+
+    // The cells in [column] that have something in them, as (row, value), top to bottom.
+    pub fn column(&self, column: i64) -> impl Iterator<Item = (i64, &T)> {
+        let mut cells: Vec<(i64, &T)> = self
+            .iter()
+            .filter(|(pos, _)| pos.1 == column)
+            .map(|(pos, value)| (pos.0, value))
+            .collect();
+        cells.sort_by_key(|(row, _)| *row);
+        cells.into_iter()
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> SparseGrid<U> {
+        self.iter().map(|(pos, value)| (pos, f(value))).collect()
+    }
+
+    // These all turn things about the origin, since there aren't any edges to keep things
+    // inside. So they match what [Grid] does up to where things end up, and [render] (which
+    // starts from [bounds]) draws the same picture.
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.moved(|(row, column)| (column, row))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.moved(|(row, column)| (column, -row))
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.moved(|(row, column)| (-column, row))
+    }
+
+    fn moved(&self, f: impl Fn((i64, i64)) -> (i64, i64)) -> Self
+    where
+        T: Clone,
+    {
+        self.iter()
+            .map(|(pos, value)| (f(pos), value.clone()))
+            .collect()
+    }
+
+    pub fn neighbours4((row, column): (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        NEIGHBOURS4
+            .iter()
+            .map(move |(d_row, d_column)| (row + *d_row as i64, column + *d_column as i64))
+    }
+
+    pub fn neighbours8((row, column): (i64, i64)) -> impl Iterator<Item = (i64, i64)> {
+        NEIGHBOURS8
+            .iter()
+            .map(move |(d_row, d_column)| (row + *d_row as i64, column + *d_column as i64))
+    }
+
+    // Everything inside [bounds], with [f] choosing the character for each cell (which might
+    // not have anything in it).
+    pub fn render(&self, f: impl Fn(Option<&T>) -> char) -> String {
+        let mut s = String::new();
+        if let Some(((min_row, min_column), (max_row, max_column))) = self.bounds() {
+            for row in min_row..=max_row {
+                s.extend((min_column..=max_column).map(|column| f(self.get((row, column)))));
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+impl<T> Index<(i64, i64)> for SparseGrid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (i64, i64)) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("BUG: nothing at {:?}", pos))
+    }
+}
+
+impl<T> IndexMut<(i64, i64)> for SparseGrid<T> {
+    fn index_mut(&mut self, pos: (i64, i64)) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("BUG: nothing at {:?}", pos))
+    }
+}
+
+impl<T> FromIterator<((i64, i64), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i64, i64), T)>>(iter: I) -> Self {
+        SparseGrid {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::from_char_map("abc\ndef\n").unwrap()
+    }
+
+    #[test]
+    fn parse_and_index() {
+        let grid = example();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 2)], 'c');
+        assert_eq!(grid[(1, 0)], 'd');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get_point(Point2::new(2, 0)), Some(&'c'));
+        assert_eq!(grid.get_point(Point2::new(-1, 0)), None);
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+
+        let digits = Grid::parse_with("12\n34\n", |c| c.to_digit(10), "a digit").unwrap();
+        assert_eq!(digits.row(1), &[3, 4]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Grid::from_char_map("abc\n\nde\n").unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.expected, "3 cells, like the first row");
+
+        let error = Grid::parse_with("12\n3x\n", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
+        assert_eq!(error.text, "x");
+
+        assert!(Grid::from_char_map("").is_err());
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::new(3, 3, 0);
+        let mut corner: Vec<Pos> = grid.neighbours4((0, 0)).collect();
+        corner.sort();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8((2, 0)).count(), 3);
+    }
+
+    #[test]
+    fn rows_columns_and_rays() {
+        let grid = example();
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![&['a', 'b', 'c'], &['d', 'e', 'f']]
+        );
+        assert_eq!(grid.column(1).collect::<String>(), "be");
+
+        let ray: Vec<Pos> = grid.ray((1, 0), (0, 1)).collect();
+        assert_eq!(ray, vec![(1, 1), (1, 2)]);
+        assert_eq!(grid.ray((1, 0), (0, -1)).count(), 0);
+        assert_eq!(grid.ray((1, 0), (-1, 1)).collect::<Vec<_>>(), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = example();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "cf\nbe\nad\n");
+        assert_eq!(
+            grid.rotate_clockwise().rotate_clockwise().to_string(),
+            "fed\ncba\n"
+        );
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
+    }
+
+    #[test]
+    fn sparse() {
+        let mut grid: SparseGrid<char> = [((0, 0), '#'), ((-1, 2), 'o')].into_iter().collect();
+        assert_eq!(grid.bounds(), Some(((-1, 0), (0, 2))));
+        assert_eq!(grid.render(|c| *c.unwrap_or(&'.')), "..o\n#..\n");
+
+        assert_eq!(grid.insert((0, 0), '~'), Some('#'));
+        assert_eq!(grid.remove((-1, 2)), Some('o'));
+        assert_eq!(grid.len(), 1);
+        assert!(grid.contains((0, 0)));
+        assert_eq!(SparseGrid::<char>::new().bounds(), None);
+
+        let neighbours: Vec<(i64, i64)> = SparseGrid::<char>::neighbours4((0, 0)).collect();
+        assert_eq!(neighbours, vec![(-1, 0), (0, 1), (1, 0), (0, -1)]);
+        assert_eq!(SparseGrid::<char>::neighbours8((5, 5)).count(), 8);
+    }
+
+    #[test]
+    fn sparse_matches_dense() {
+        let dense = example();
+        let sparse: SparseGrid<char> = dense
+            .iter()
+            .map(|((row, column), c)| ((row as i64, column as i64), *c))
+            .collect();
+        let render = |grid: &SparseGrid<char>| grid.render(|c| *c.unwrap_or(&'.'));
+        assert_eq!(render(&sparse), dense.to_string());
+        assert_eq!(render(&sparse.transpose()), dense.transpose().to_string());
+        assert_eq!(
+            render(&sparse.rotate_clockwise()),
+            dense.rotate_clockwise().to_string()
+        );
+        assert_eq!(
+            render(&sparse.rotate_anticlockwise()),
+            dense.rotate_anticlockwise().to_string()
+        );
+        assert_eq!(sparse.rotate_clockwise().rotate_anticlockwise(), sparse);
+        assert_eq!(
+            render(&sparse.map(|c| c.to_ascii_uppercase())),
+            "ABC\nDEF\n"
+        );
+    }
+
+    #[test]
+    fn sparse_rows_and_columns() {
+        let mut grid: SparseGrid<char> = [((0, 3), 'b'), ((0, -1), 'a'), ((2, 3), 'c')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.row(0).collect::<Vec<_>>(), vec![(-1, &'a'), (3, &'b')]);
+        assert_eq!(grid.row(1).count(), 0);
+        assert_eq!(
+            grid.column(3).collect::<Vec<_>>(),
+            vec![(0, &'b'), (2, &'c')]
+        );
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            vec![(0, vec![(-1, &'a'), (3, &'b')]), (2, vec![(3, &'c')])]
+        );
+
+        grid[(2, 3)] = 'd';
+        assert_eq!(grid[(2, 3)], 'd');
+        assert_eq!(grid.get_mut((5, 5)), None);
+    }
+}
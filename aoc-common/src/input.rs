@@ -0,0 +1,146 @@
+use std::fs::read_to_string;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+
+pub fn read_input<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    read_to_string(path)
+}
+
+// The non-empty lines of the input. Using [str::lines] rather than splitting on '\n' means
+// we also cope with a trailing '\r' if the input was saved on Windows.
+pub fn lines(s: &str) -> Vec<&str> {
+    s.lines().filter(|line| !line.is_empty()).collect()
+}
+
+// Like [lines], but with the (1-indexed) line number of each, for reporting errors.
+pub fn numbered_lines(s: &str) -> Vec<(usize, &str)> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(i, line)| (i + 1, line))
+        .collect()
+}
+
+// Parse every non-empty line, tagging any error with the line it came from.
+pub fn parse_lines_with<T>(
+    s: &str,
+    parse: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    numbered_lines(s)
+        .into_iter()
+        .map(|(line_number, line)| parse(line).map_err(|e| e.at_line(line_number)))
+        .collect()
+}
+
+pub fn parse_lines<T: FromStr<Err = ParseError>>(s: &str) -> Result<Vec<T>, ParseError> {
+    parse_lines_with(s, str::parse)
+}
+
+// Parse a number that starts at [column] of the line.
+pub fn parse_number<T: FromStr>(s: &str, column: usize) -> Result<T, ParseError> {
+    s.parse()
+        .map_err(|_| ParseError::at_column(column, s, "a number"))
+}
+
+// Groups of lines separated by blank lines, e.g. the elves in day 1.
+// Runs of several blank lines (or blank lines at the start/end) don't create empty chunks.
+pub fn chunks(s: &str) -> Vec<Vec<&str>> {
+    numbered_chunks(s)
+        .into_iter()
+        .map(|chunk| chunk.into_iter().map(|(_, line)| line).collect())
+        .collect()
+}
+
+// Like [chunks], but with the (1-indexed) line number of each line.
+pub fn numbered_chunks(s: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut chunks: Vec<Vec<(usize, &str)>> = vec![];
+    let mut current: Vec<(usize, &str)> = vec![];
+
+    for (i, line) in s.lines().enumerate() {
+        if line.is_empty() {
+            if !current.is_empty() {
+                chunks.push(current);
+                current = vec![];
+            }
+        } else {
+            current.push((i + 1, line))
+        }
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+// Pull out every integer in a string, ignoring anything else around it.
+// e.g. "Sensor at x=2, y=-18" gives [2, -18]. A '-' only counts as a sign if it's
+// immediately followed by a digit.
+pub fn extract_ints(s: &str) -> Vec<i64> {
+    let mut result = vec![];
+    let mut current = String::new();
+
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let starts_negative_number =
+            c == '-' && current.is_empty() && chars.peek().is_some_and(char::is_ascii_digit);
+
+        if c.is_ascii_digit() || starts_negative_number {
+            current.push(c);
+        } else if !current.is_empty() {
+            result.push(current.parse().expect("BUG: extracted non-integer"));
+            current.clear();
+        }
+    }
+    if !current.is_empty() {
+        result.push(current.parse().expect("BUG: extracted non-integer"));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_skips_blank_lines_and_carriage_returns() {
+        assert_eq!(lines("a\r\nb\n\nc\n"), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn chunks_are_split_on_blank_lines() {
+        let s = "\n1\n2\n\n3\n\n\n4\n";
+        assert_eq!(chunks(s), vec![vec!["1", "2"], vec!["3"], vec!["4"]]);
+        assert_eq!(
+            numbered_chunks(s),
+            vec![vec![(2, "1"), (3, "2")], vec![(5, "3")], vec![(8, "4")]]
+        );
+    }
+
+    #[test]
+    fn extract_ints_handles_signs_and_punctuation() {
+        assert_eq!(
+            extract_ints("Sensor at x=2, y=-18: closest beacon is at x=-2, y=15"),
+            vec![2, -18, -2, 15]
+        );
+        // A dash straight after a number is a separator, not a sign.
+        assert_eq!(extract_ints("a-b 3-4 -5"), vec![3, 4, -5]);
+        assert_eq!(extract_ints("no numbers"), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn parse_lines_reports_the_line_number() {
+        let result: Result<Vec<usize>, ParseError> =
+            parse_lines_with("1\n\n2\nx\n", |s| parse_number(s, 1));
+        let error = result.unwrap_err();
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.column, Some(1));
+        assert_eq!(error.text, "x");
+
+        let result: Result<Vec<usize>, ParseError> =
+            parse_lines_with("1\n\n2\n", |s| parse_number(s, 1));
+        assert_eq!(result, Ok(vec![1, 2]));
+    }
+}
@@ -0,0 +1,406 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ParseError;
+use crate::input::parse_number;
+use crate::point::Coord;
+
+// Closed intervals of integers, and sets of them. Everything's inclusive at both ends, since
+// that's how the puzzles describe ranges ("2-4" means sections 2, 3 and 4).
+//
+// Nothing here is careful about overflow at the very ends of the integer types, so don't put
+// an interval ending at i64::MAX in a set.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Coord> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        assert!(start <= end, "BUG: interval can't end before it starts");
+        Interval { start, end }
+    }
+
+    pub fn single(x: T) -> Self {
+        Interval::new(x, x)
+    }
+
+    // The number of integers in the interval.
+    pub fn len(&self) -> T {
+        self.end - self.start + T::ONE
+    }
+
+    pub fn contains(&self, other: &Self) -> bool {
+        self.start <= other.start && self.end >= other.end
+    }
+
+    pub fn contains_point(&self, x: T) -> bool {
+        self.start <= x && x <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        let do_not_overlap = self.start > other.end || other.start > self.end;
+
+        !do_not_overlap
+    }
+
+    // Whether the union is a single interval, e.g. 1-3 and 4-5.
+    fn overlaps_or_adjacent(&self, other: &Self) -> bool {
+        self.start <= other.end + T::ONE && other.start <= self.end + T::ONE
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Interval::new(
+                self.start.max(other.start),
+                self.end.min(other.end),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+// Reads an interval written like the puzzles do, e.g. "2-4". The start can be negative ("-3-5"),
+// so we split on the first '-' after the first character.
+impl<T: Coord + FromStr> FromStr for Interval<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let split = s
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(i, _)| i)
+            .ok_or_else(|| ParseError::at_column(1, s, "an interval like 2-4"))?;
+        let (start, end) = (&s[..split], &s[split + 1..]);
+
+        let start = parse_number(start, 1)?;
+        let end = parse_number(end, split + 2)?;
+        if end < start {
+            return Err(ParseError::at_column(
+                1,
+                s,
+                "an interval that ends after it starts",
+            ));
+        }
+        Ok(Interval::new(start, end))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+// A set of integers, stored as the intervals that make it up. They're kept sorted, and any that
+// overlap or touch are merged, so there's only ever one way of writing down a given set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+}
+
+impl<T: Coord> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // The number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |total, interval| total + interval.len())
+    }
+
+    // The index of the first interval that [interval] could be merged with, or the place it
+    // would be inserted if there isn't one.
+    fn first_touching(&self, interval: &Interval<T>) -> usize {
+        self.intervals
+            .partition_point(|existing| existing.end + T::ONE < interval.start)
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        let first = self.first_touching(&interval);
+        let last = first
+            + self.intervals[first..]
+                .iter()
+                .take_while(|existing| existing.overlaps_or_adjacent(&interval))
+                .count();
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, existing| {
+                Interval::new(
+                    merged.start.min(existing.start),
+                    merged.end.max(existing.end),
+                )
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        let first = self.first_touching(&interval);
+        let last = first
+            + self.intervals[first..]
+                .iter()
+                .take_while(|existing| existing.start <= interval.end)
+                .count();
+
+        // Whatever's left of the intervals we cut into.
+        let mut pieces = vec![];
+        for existing in &self.intervals[first..last] {
+            if existing.start < interval.start {
+                pieces.push(Interval::new(existing.start, interval.start - T::ONE));
+            }
+            if existing.end > interval.end {
+                pieces.push(Interval::new(interval.end + T::ONE, existing.end));
+            }
+        }
+        self.intervals.splice(first..last, pieces);
+    }
+
+    pub fn contains(&self, x: T) -> bool {
+        let i = self.intervals.partition_point(|interval| interval.end < x);
+        self.intervals
+            .get(i)
+            .is_some_and(|interval| interval.start <= x)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals
+            .iter()
+            .chain(other.intervals.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        // Both lists are sorted, so walk along them together.
+        let mut result = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (&self.intervals[i], &other.intervals[j]);
+            result.extend(a.intersection(b));
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals: result }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for interval in &other.intervals {
+            result.remove(*interval);
+        }
+        result
+    }
+
+    // Everything in [bounds] that isn't in the set.
+    pub fn complement(&self, bounds: Interval<T>) -> Self {
+        IntervalSet {
+            intervals: self.gaps(bounds).collect(),
+        }
+    }
+
+    // The bits of [bounds] not covered by the set, in order.
+    pub fn gaps(&self, bounds: Interval<T>) -> impl Iterator<Item = Interval<T>> {
+        let mut gaps = vec![];
+        let mut next_uncovered = bounds.start;
+
+        let first = self.first_touching(&bounds);
+        for interval in self.intervals[first..]
+            .iter()
+            .take_while(|interval| interval.start <= bounds.end)
+        {
+            if interval.start > next_uncovered {
+                gaps.push(Interval::new(next_uncovered, interval.start - T::ONE));
+            }
+            next_uncovered = next_uncovered.max(interval.end + T::ONE);
+        }
+
+        if next_uncovered <= bounds.end {
+            gaps.push(Interval::new(next_uncovered, bounds.end));
+        }
+        gaps.into_iter()
+    }
+}
+
+// Sort everything and then sweep through once, merging as we go.
+impl<T: Coord> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut sorted: Vec<Interval<T>> = iter.into_iter().collect();
+        sorted.sort_by_key(|interval| interval.start);
+
+        let mut intervals: Vec<Interval<T>> = vec![];
+        for interval in sorted {
+            match intervals.last_mut() {
+                Some(last) if last.overlaps_or_adjacent(&interval) => {
+                    last.end = last.end.max(interval.end)
+                }
+                _ => intervals.push(interval),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    fn pairs(set: &IntervalSet<i64>) -> Vec<(i64, i64)> {
+        set.intervals()
+            .iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    #[test]
+    fn interval() {
+        let interval = Interval::new(2, 8);
+        assert_eq!(interval.len(), 7);
+        assert!(interval.contains(&Interval::new(3, 7)));
+        assert!(!interval.contains(&Interval::new(3, 9)));
+        assert!(interval.overlaps(&Interval::new(8, 9)));
+        assert!(!interval.overlaps(&Interval::new(9, 9)));
+        assert!(interval.contains_point(8));
+        assert_eq!(
+            interval.intersection(&Interval::new(5, 12)),
+            Some(Interval::new(5, 8))
+        );
+        assert_eq!(interval.intersection(&Interval::new(0, 1)), None);
+        assert_eq!(interval.to_string(), "2-8");
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("2-8".parse(), Ok(Interval::new(2, 8)));
+        assert_eq!("-3--1".parse(), Ok(Interval::new(-3, -1)));
+        assert_eq!("5-5".parse(), Ok(Interval::single(5)));
+
+        let error = |s: &str| s.parse::<Interval<i64>>().unwrap_err().to_string();
+        assert_eq!(
+            error("28"),
+            "column 1: expected an interval like 2-4, found \"28\""
+        );
+        assert_eq!(error("2-x"), "column 3: expected a number, found \"x\"");
+        assert_eq!(
+            error("8-2"),
+            "column 1: expected an interval that ends after it starts, found \"8-2\""
+        );
+    }
+
+    #[test]
+    fn merges_overlapping_and_adjacent() {
+        let intervals = set(&[(12, 12), (2, 14), (16, 24), (15, 15), (30, 31)]);
+        assert_eq!(pairs(&intervals), vec![(2, 24), (30, 31)]);
+        assert_eq!(intervals.len(), 25);
+
+        let intervals = set(&[(5, 5), (0, 3)]);
+        assert_eq!(pairs(&intervals), vec![(0, 3), (5, 5)]);
+        assert_eq!(intervals.len(), 5);
+
+        assert!(set(&[]).is_empty());
+        assert_eq!(set(&[]).len(), 0);
+    }
+
+    #[test]
+    fn insert() {
+        let mut intervals = set(&[(0, 2), (6, 7), (10, 12), (20, 20)]);
+        intervals.insert(Interval::new(4, 4));
+        assert_eq!(
+            pairs(&intervals),
+            vec![(0, 2), (4, 4), (6, 7), (10, 12), (20, 20)]
+        );
+
+        intervals.insert(Interval::new(3, 9));
+        assert_eq!(pairs(&intervals), vec![(0, 12), (20, 20)]);
+
+        intervals.insert(Interval::new(25, 30));
+        intervals.insert(Interval::new(-5, -5));
+        assert_eq!(
+            pairs(&intervals),
+            vec![(-5, -5), (0, 12), (20, 20), (25, 30)]
+        );
+    }
+
+    #[test]
+    fn remove() {
+        let mut intervals = set(&[(0, 10), (20, 30)]);
+        intervals.remove(Interval::new(3, 4));
+        assert_eq!(pairs(&intervals), vec![(0, 2), (5, 10), (20, 30)]);
+
+        intervals.remove(Interval::new(8, 25));
+        assert_eq!(pairs(&intervals), vec![(0, 2), (5, 7), (26, 30)]);
+
+        intervals.remove(Interval::new(-10, 100));
+        assert!(intervals.is_empty());
+    }
+
+    #[test]
+    fn contains() {
+        let intervals = set(&[(0, 2), (6, 7)]);
+        let members: Vec<i64> = (-1..=8).filter(|x| intervals.contains(*x)).collect();
+        assert_eq!(members, vec![0, 1, 2, 6, 7]);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (40, 41)]);
+        assert_eq!(pairs(&a.union(&b)), vec![(0, 30), (40, 41)]);
+        assert_eq!(pairs(&a.intersection(&b)), vec![(5, 10), (20, 25)]);
+        assert_eq!(pairs(&a.difference(&b)), vec![(0, 4), (26, 30)]);
+        assert_eq!(pairs(&b.difference(&a)), vec![(11, 19), (40, 41)]);
+    }
+
+    #[test]
+    fn complement_and_gaps() {
+        let intervals = set(&[(-3, 13), (15, 25)]);
+        assert_eq!(
+            pairs(&intervals.complement(Interval::new(0, 20))),
+            vec![(14, 14)]
+        );
+        assert_eq!(
+            pairs(&intervals.complement(Interval::new(-10, 30))),
+            vec![(-10, -4), (14, 14), (26, 30)]
+        );
+        assert!(intervals.complement(Interval::new(0, 13)).is_empty());
+        assert!(intervals.complement(Interval::new(15, 20)).is_empty());
+        assert_eq!(
+            intervals.gaps(Interval::new(40, 50)).collect::<Vec<_>>(),
+            vec![Interval::new(40, 50)]
+        );
+        assert_eq!(
+            set(&[]).gaps(Interval::new(1, 2)).collect::<Vec<_>>(),
+            vec![Interval::new(1, 2)]
+        );
+    }
+}
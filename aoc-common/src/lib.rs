@@ -0,0 +1,14 @@
+// Bits and pieces shared between the days, so I stop copy-pasting the same input handling
+// into every crate.
+
+pub mod cli;
+pub mod error;
+pub mod graph;
+pub mod grid;
+pub mod input;
+pub mod intervals;
+pub mod point;
+pub mod solution;
+
+pub use error::ParseError;
+pub use solution::{Answer, Solution};
@@ -0,0 +1,272 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::grid::Pos;
+use crate::ParseError;
+
+// A point (or a vector - same thing) in 2D. Like the grids, y increases going down the page, so
+// [Dir::Up] is y - 1.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+// The integer types we use as co-ordinates (including the 1D ones in [intervals]).
+pub trait Coord: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    // |self - other|, without overflowing for unsigned types.
+    fn distance(self, other: Self) -> Self;
+}
+
+macro_rules! signed_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                (self - other).abs()
+            }
+        })*
+    };
+}
+
+macro_rules! unsigned_coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn distance(self, other: Self) -> Self {
+                self.abs_diff(other)
+            }
+        })*
+    };
+}
+
+signed_coord!(i32, i64, isize);
+unsigned_coord!(u32, u64, usize);
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Coord> Point2<T> {
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    // The number of king's moves between the points, i.e. diagonal steps are allowed.
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    // The (row, column) of this point in a grid of the given size, if it's on it.
+    pub fn grid_index(&self, width: usize, height: usize) -> Option<Pos>
+    where
+        usize: TryFrom<T>,
+    {
+        let row = usize::try_from(self.y).ok()?;
+        let column = usize::try_from(self.x).ok()?;
+        if row < height && column < width {
+            Some((row, column))
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Copy> Point2<T> {
+    // For grids that don't have edges, so any (row, column) is fine.
+    pub fn row_column(&self) -> (T, T) {
+        (self.y, self.x)
+    }
+}
+
+impl Point2<usize> {
+    pub fn from_grid_index((row, column): Pos) -> Self {
+        Point2::new(column, row)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+// Scaling by a constant
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Point2::new(self.x * k, self.y * k)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+use Dir::*;
+
+impl Dir {
+    // Clockwise, starting from up.
+    pub const ALL: [Dir; 4] = [Up, Right, Down, Left];
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Up => Right,
+            Right => Down,
+            Down => Left,
+            Left => Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Up => Left,
+            Left => Down,
+            Down => Right,
+            Right => Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    // The step you take moving one square in this direction.
+    pub fn offset<T: From<i8>>(self) -> Point2<T> {
+        let (x, y) = match self {
+            Up => (0, -1),
+            Right => (1, 0),
+            Down => (0, 1),
+            Left => (-1, 0),
+        };
+        Point2::new(T::from(x), T::from(y))
+    }
+}
+
+// The usual single-letter form, as in day 9.
+impl FromStr for Dir {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" => Ok(Up),
+            "R" => Ok(Right),
+            "D" => Ok(Down),
+            "L" => Ok(Left),
+            _ => Err(ParseError::at_column(1, s, "a direction (U, D, L or R)")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let p = Point2::new(3, -2);
+        let q = Point2::new(1, 5);
+        assert_eq!(p + q, Point2::new(4, 3));
+        assert_eq!(p - q, Point2::new(2, -7));
+        assert_eq!(-p, Point2::new(-3, 2));
+        assert_eq!(p * 3, Point2::new(9, -6));
+
+        let mut r = p;
+        r += q;
+        r -= Point2::new(4, 4);
+        assert_eq!(r, Point2::new(0, -1));
+    }
+
+    #[test]
+    fn distances() {
+        let p: Point2<i64> = Point2::new(2, 18);
+        let q = Point2::new(-2, 15);
+        assert_eq!(p.manhattan_distance(&q), 7);
+        assert_eq!(p.chebyshev_distance(&q), 4);
+
+        let a: Point2<usize> = Point2::new(1, 10);
+        let b = Point2::new(4, 2);
+        assert_eq!(a.manhattan_distance(&b), 11);
+        assert_eq!(b.chebyshev_distance(&a), 8);
+    }
+
+    #[test]
+    fn turning() {
+        assert_eq!(Up.turn_right(), Right);
+        assert_eq!(Up.turn_left(), Left);
+        assert_eq!(Left.reverse(), Right);
+        for dir in Dir::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(
+                dir.offset::<i64>() + dir.reverse().offset(),
+                Point2::new(0, 0)
+            );
+        }
+        assert_eq!(Up.offset::<i32>(), Point2::new(0, -1));
+        assert_eq!(Right.offset::<i32>(), Point2::new(1, 0));
+    }
+
+    #[test]
+    fn grid_indices() {
+        assert_eq!(Point2::new(2, 1).grid_index(3, 2), Some((1, 2)));
+        assert_eq!(Point2::new(3, 1).grid_index(3, 2), None);
+        assert_eq!(Point2::new(0, 2).grid_index(3, 2), None);
+        assert_eq!(Point2::new(-1_i64, 0).grid_index(3, 2), None);
+        assert_eq!(Point2::from_grid_index((1, 2)), Point2::new(2, 1));
+        assert_eq!(Point2::new(500, 9).row_column(), (9, 500));
+    }
+
+    #[test]
+    fn parse_dir() {
+        assert_eq!("U".parse::<Dir>(), Ok(Up));
+        assert_eq!("L".parse::<Dir>(), Ok(Left));
+        assert_eq!("X".parse::<Dir>().unwrap_err().column, Some(1));
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+use std::process;
+
+use crate::cli::{usage_error, Args, InputSource};
+use crate::error::ParseError;
+
+// What a part of a puzzle produces. Most days are a number, but some want a string
+// (day 5's top crates), and day 10 draws letters on a screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    // Several lines of output, to be read by a human.
+    Render(String),
+}
+
+impl Answer {
+    pub fn is_multiline(&self) -> bool {
+        matches!(self, Answer::Render(_))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{}", i),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Render(s) => write!(f, "{}", s.trim_end()),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(i: i64) -> Self {
+        Answer::Int(i)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(i: usize) -> Self {
+        Answer::Int(i as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+// A day's puzzle, split into its stages: parse the input once, then solve each part from
+// the parsed form. Keeping these separate is what lets us test and time them individually.
+pub trait Solution {
+    const DAY: u8;
+
+    // The example input from the puzzle text.
+    const EXAMPLE: &'static str;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    // The examples sometimes use different constants to the real puzzle (e.g. which row day 15
+    // asks about), so days can override this to account for that.
+    fn parse_example() -> Result<Self::Input, ParseError> {
+        Self::parse(Self::EXAMPLE)
+    }
+
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+}
+
+pub fn load<S: Solution>(source: &InputSource) -> Result<S::Input, String> {
+    let parsed = match source {
+        InputSource::Example => S::parse_example(),
+        _ => S::parse(&source.read(S::EXAMPLE)?),
+    };
+    parsed.map_err(|e| format!("Day {}: {}", S::DAY, e))
+}
+
+pub fn print_answers<S: Solution>(parsed: &S::Input) {
+    for (part, answer) in [(1, S::part1(parsed)), (2, S::part2(parsed))] {
+        if answer.is_multiline() {
+            println!("Part {}:\n{}", part, answer);
+        } else {
+            println!("Part {}: {}", part, answer);
+        }
+    }
+}
+
+const USAGE: &str = "\
+Usage: day<N> [<input> | - | --example]
+
+<input> defaults to the input file in the day's src directory. Pass - to read from stdin,
+or --example to use the example from the puzzle text.";
+
+// What each day's binary does: work out where the input is, parse it, then print both parts.
+pub fn run_main<S: Solution>(default_input: &str) {
+    let mut args = Args::from_env();
+    let source = args
+        .input_source(default_input)
+        .and_then(|source| args.finish().map(|()| source))
+        .unwrap_or_else(|e| usage_error(USAGE, &e));
+
+    match load::<S>(&source) {
+        Ok(parsed) => print_answers::<S>(&parsed),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1)
+        }
+    }
+}
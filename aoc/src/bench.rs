@@ -0,0 +1,175 @@
+use std::time::{Duration, Instant};
+
+use aoc_common::cli::InputSource;
+
+use crate::days::{Day, Parsed};
+
+// Timing each stage separately, so it's obvious whether a slow day is slow to parse or slow to
+// solve. The input is only read once, since we're not interested in how fast the disk is.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "BUG: no samples to take stats of");
+        samples.sort();
+
+        let middle = samples.len() / 2;
+        let median = if samples.len() % 2 == 1 {
+            samples[middle]
+        } else {
+            (samples[middle - 1] + samples[middle]) / 2
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct DayTimings {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayTimings {
+    pub fn stages(&self) -> [(&'static str, Stats); 3] {
+        [
+            ("parse", self.parse),
+            ("part1", self.part1),
+            ("part2", self.part2),
+        ]
+    }
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn bench_day(day: &Day, source: &InputSource, iterations: usize) -> Result<DayTimings, String> {
+    let input = source.read(day.example)?;
+    let parse = || -> Result<Parsed, String> {
+        let parsed = match source {
+            InputSource::Example => (day.parse_example)(),
+            _ => (day.parse)(&input),
+        };
+        parsed.map_err(|e| format!("Day {}: {}", day.number, e))
+    };
+
+    let mut parse_times = vec![];
+    let mut part1_times = vec![];
+    let mut part2_times = vec![];
+    for _ in 0..iterations {
+        let (parsed, parse_time) = time(parse);
+        let parsed = parsed?;
+        parse_times.push(parse_time);
+        part1_times.push(time(|| (day.part1)(&parsed)).1);
+        part2_times.push(time(|| (day.part2)(&parsed)).1);
+    }
+
+    Ok(DayTimings {
+        day: day.number,
+        parse: Stats::from_samples(parse_times),
+        part1: Stats::from_samples(part1_times),
+        part2: Stats::from_samples(part2_times),
+    })
+}
+
+// Times are in nanoseconds, so there's no arguing about units when comparing runs.
+pub fn to_json(iterations: usize, results: &[DayTimings]) -> String {
+    let days: Vec<String> = results
+        .iter()
+        .map(|result| {
+            let stages: Vec<String> = result
+                .stages()
+                .iter()
+                .map(|(name, stats)| {
+                    format!(
+                        "\"{}\": {{\"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}}}",
+                        name,
+                        stats.min.as_nanos(),
+                        stats.median.as_nanos(),
+                        stats.max.as_nanos()
+                    )
+                })
+                .collect();
+            format!(
+                "    {{\"day\": {}, \"stages\": {{{}}}}}",
+                result.day,
+                stages.join(", ")
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"iterations\": {},\n  \"days\": [\n{}\n  ]\n}}\n",
+        iterations,
+        days.join(",\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(ms: &[u64]) -> Vec<Duration> {
+        ms.iter().map(|ms| Duration::from_millis(*ms)).collect()
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(millis(&[5, 1, 3]));
+        assert_eq!(
+            stats,
+            Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                max: Duration::from_millis(5),
+            }
+        );
+
+        // With an even number of samples, the median is halfway between the middle two.
+        let stats = Stats::from_samples(millis(&[8, 2, 4, 1]));
+        assert_eq!(stats.median, Duration::from_millis(3));
+
+        let stats = Stats::from_samples(millis(&[7]));
+        assert_eq!(stats.min, Duration::from_millis(7));
+        assert_eq!(stats.median, stats.min);
+        assert_eq!(stats.max, stats.min);
+    }
+
+    #[test]
+    fn json() {
+        let stats = |ns| Stats {
+            min: Duration::from_nanos(ns),
+            median: Duration::from_nanos(ns * 2),
+            max: Duration::from_nanos(ns * 3),
+        };
+        let results = [DayTimings {
+            day: 4,
+            parse: stats(1),
+            part1: stats(10),
+            part2: stats(100),
+        }];
+
+        let expected = r#"{
+  "iterations": 3,
+  "days": [
+    {"day": 4, "stages": {"parse": {"min_ns": 1, "median_ns": 2, "max_ns": 3}, "part1": {"min_ns": 10, "median_ns": 20, "max_ns": 30}, "part2": {"min_ns": 100, "median_ns": 200, "max_ns": 300}}}
+  ]
+}
+"#;
+        assert_eq!(to_json(3, &results), expected);
+    }
+}
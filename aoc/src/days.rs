@@ -0,0 +1,115 @@
+use std::any::Any;
+
+use aoc_common::cli::InputSource;
+use aoc_common::{Answer, ParseError, Solution};
+
+// Every day that has a Rust solution. Day 11 (and 16 onwards) were done in Python, and the
+// Rust attempt at day 16 never got as far as producing an answer, so they're not in here.
+//
+// Each day's [Solution] has its own input type, so to keep them all in one list we box the
+// parsed input up as [Any] and have each day downcast it back again.
+
+pub type Parsed = Box<dyn Any>;
+
+pub struct Day {
+    pub number: u8,
+    pub example: &'static str,
+    pub parse: fn(&str) -> Result<Parsed, ParseError>,
+    pub parse_example: fn() -> Result<Parsed, ParseError>,
+    pub part1: fn(&Parsed) -> Answer,
+    pub part2: fn(&Parsed) -> Answer,
+}
+
+impl Day {
+    pub fn part(&self, part: u8) -> fn(&Parsed) -> Answer {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => panic!("BUG: asked for part {}, there are only two", part),
+        }
+    }
+
+    pub fn load(&self, source: &InputSource) -> Result<Parsed, String> {
+        let parsed = match source {
+            InputSource::Example => (self.parse_example)(),
+            _ => (self.parse)(&source.read(self.example)?),
+        };
+        parsed.map_err(|e| format!("Day {}: {}", self.number, e))
+    }
+
+    // Where the puzzle input lives, relative to the root of the repo.
+    pub fn default_input_path(&self) -> String {
+        format!("day{}/src/input", self.number)
+    }
+}
+
+fn parse<S: Solution>(input: &str) -> Result<Parsed, ParseError>
+where
+    S::Input: 'static,
+{
+    Ok(Box::new(S::parse(input)?))
+}
+
+fn parse_example<S: Solution>() -> Result<Parsed, ParseError>
+where
+    S::Input: 'static,
+{
+    Ok(Box::new(S::parse_example()?))
+}
+
+fn downcast<S: Solution>(parsed: &Parsed) -> &S::Input
+where
+    S::Input: 'static,
+{
+    parsed
+        .downcast_ref()
+        .expect("BUG: parsed input passed to the wrong day")
+}
+
+fn part1<S: Solution>(parsed: &Parsed) -> Answer
+where
+    S::Input: 'static,
+{
+    S::part1(downcast::<S>(parsed))
+}
+
+fn part2<S: Solution>(parsed: &Parsed) -> Answer
+where
+    S::Input: 'static,
+{
+    S::part2(downcast::<S>(parsed))
+}
+
+macro_rules! day {
+    ($solution:ty) => {
+        Day {
+            number: <$solution>::DAY,
+            example: <$solution>::EXAMPLE,
+            parse: parse::<$solution>,
+            parse_example: parse_example::<$solution>,
+            part1: part1::<$solution>,
+            part2: part2::<$solution>,
+        }
+    };
+}
+
+pub const DAYS: &[Day] = &[
+    day!(day1::Day1),
+    day!(day2::Day2),
+    day!(day3::Day3),
+    day!(day4::Day4),
+    day!(day5::Day5),
+    day!(day6::Day6),
+    day!(day7::Day7),
+    day!(day8::Day8),
+    day!(day9::Day9),
+    day!(day10::Day10),
+    day!(day12::Day12),
+    day!(day13::Day13),
+    day!(day14::Day14),
+    day!(day15::Day15),
+];
+
+pub fn find(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
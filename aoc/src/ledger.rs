@@ -0,0 +1,273 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use aoc_common::input::parse_number;
+use aoc_common::ParseError;
+
+// The answers we've had accepted on the website, so [aoc verify] can tell us if a refactor has
+// broken anything. It lives in answers.toml at the root of the repo, and looks like:
+//
+//     [[answer]]
+//     day = 1
+//     part = 1
+//     input = "5ad8e2c1f0b7d4a3"
+//     expected = "69836"
+//
+// [input] is a hash of the puzzle input, so that swapping in a different input shows up as a
+// new answer rather than a mismatch. I don't want a dependency just for this, so the parser only
+// understands the tiny bit of TOML that this file actually uses.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub expected: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Ledger {
+    pub entries: Vec<Entry>,
+}
+
+const HEADER: &str = "\
+# Answers that have been accepted on the Advent of Code website, keyed by a hash of the input.
+# Check them with `aoc verify`, and add new ones with `aoc verify --record`.
+";
+
+// 64-bit FNV-1a. Nothing clever, we only need to tell inputs apart.
+pub fn hash_input(input: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+fn escape(s: &str) -> String {
+    let mut result = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+// [value] is everything after the '=' (with whitespace trimmed), and [column] is where it
+// started, for error messages.
+fn parse_string(value: &str, column: usize) -> Result<String, ParseError> {
+    let error = || ParseError::at_column(column, value, "a string in double quotes");
+
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(error)?;
+
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('\\') => result.push('\\'),
+                Some('"') => result.push('"'),
+                Some('n') => result.push('\n'),
+                _ => return Err(error()),
+            },
+            '"' => return Err(error()),
+            _ => result.push(c),
+        }
+    }
+    Ok(result)
+}
+
+// An [[answer]] table that we're halfway through reading.
+#[derive(Default)]
+struct PartialEntry {
+    // Where the table started, in case it's missing something.
+    line: usize,
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    expected: Option<String>,
+}
+
+impl PartialEntry {
+    fn finish(self) -> Result<Entry, ParseError> {
+        let missing = |key: &str| {
+            ParseError::new("[[answer]]", format!("a value for {}", key)).at_line(self.line)
+        };
+        Ok(Entry {
+            day: self.day.ok_or_else(|| missing("day"))?,
+            part: self.part.ok_or_else(|| missing("part"))?,
+            input: self.input.ok_or_else(|| missing("input"))?,
+            expected: self.expected.ok_or_else(|| missing("expected"))?,
+        })
+    }
+}
+
+impl Ledger {
+    pub fn parse(s: &str) -> Result<Ledger, ParseError> {
+        let mut entries = vec![];
+        let mut current: Option<PartialEntry> = None;
+
+        for (i, line) in s.lines().enumerate() {
+            let line_number = i + 1;
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if trimmed == "[[answer]]" {
+                if let Some(entry) = current.take() {
+                    entries.push(entry.finish()?);
+                }
+                current = Some(PartialEntry {
+                    line: line_number,
+                    ..PartialEntry::default()
+                });
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::at_column(1, line, "[[answer]] or key = value"))?;
+            let value_column = key.len() + 2 + (value.len() - value.trim_start().len());
+            let (key, value) = (key.trim(), value.trim());
+
+            let entry = current
+                .as_mut()
+                .ok_or_else(|| ParseError::at_column(1, line, "[[answer]] before the first key"))?;
+            let result = match key {
+                "day" => parse_number(value, value_column).map(|day| entry.day = Some(day)),
+                "part" => parse_number(value, value_column).map(|part| entry.part = Some(part)),
+                "input" => parse_string(value, value_column).map(|s| entry.input = Some(s)),
+                "expected" => parse_string(value, value_column).map(|s| entry.expected = Some(s)),
+                _ => Err(ParseError::at_column(
+                    1,
+                    key,
+                    "one of day, part, input or expected",
+                )),
+            };
+            result.map_err(|e| e.at_line(line_number))?;
+        }
+
+        if let Some(entry) = current {
+            entries.push(entry.finish()?);
+        }
+        Ok(Ledger { entries })
+    }
+
+    // A ledger that doesn't exist yet is just an empty one.
+    pub fn load(path: &Path) -> Result<Ledger, String> {
+        match fs::read_to_string(path) {
+            Ok(s) => Ledger::parse(&s).map_err(|e| format!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Ledger::default()),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn find(&self, day: u8, part: u8, input: &str) -> Option<&Entry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.day == day && entry.part == part && entry.input == input)
+    }
+
+    pub fn add(&mut self, entry: Entry) {
+        self.entries.push(entry);
+        self.entries
+            .sort_by(|a, b| (a.day, a.part, &a.input).cmp(&(b.day, b.part, &b.input)));
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut s = HEADER.to_string();
+        for entry in &self.entries {
+            s.push_str(&format!(
+                "\n[[answer]]\nday = {}\npart = {}\ninput = \"{}\"\nexpected = \"{}\"\n",
+                entry.day,
+                entry.part,
+                escape(&entry.input),
+                escape(&entry.expected)
+            ));
+        }
+        s
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_toml())
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(day: u8, part: u8, input: &str, expected: &str) -> Entry {
+        Entry {
+            day,
+            part,
+            input: input.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq!(hash_input(""), "cbf29ce484222325");
+        assert_eq!(hash_input("a"), "af63dc4c8601ec8c");
+        assert_ne!(hash_input("1\n2\n"), hash_input("1\n2"));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut ledger = Ledger::default();
+        ledger.add(entry(10, 2, "beef", "#..#\n\"quoted\" \\ backslash"));
+        ledger.add(entry(1, 1, "cafe", "69836"));
+        ledger.add(entry(1, 2, "cafe", "207968"));
+        assert_eq!(
+            ledger.entries.iter().map(|e| e.day).collect::<Vec<_>>(),
+            vec![1, 1, 10]
+        );
+
+        assert_eq!(Ledger::parse(&ledger.to_toml()), Ok(ledger));
+    }
+
+    #[test]
+    fn parse_allows_comments_and_spacing() {
+        let s = "\
+# comment
+
+[[answer]]
+  day=3
+part = 2
+expected = \"70\"
+input = \"abc\"
+";
+        let ledger = Ledger::parse(s).unwrap();
+        assert_eq!(ledger.entries, vec![entry(3, 2, "abc", "70")]);
+        assert!(ledger.find(3, 2, "abc").is_some());
+        assert!(ledger.find(3, 1, "abc").is_none());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = Ledger::parse("[[answer]]\nday = x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(7)));
+
+        let error = Ledger::parse("[[answer]]\nday = 1\npart = 1\ninput = \"a\"\n").unwrap_err();
+        assert_eq!(error.line, Some(1));
+        assert_eq!(error.expected, "a value for expected");
+
+        let error = Ledger::parse("[[answer]]\ncolour = \"red\"\n").unwrap_err();
+        assert_eq!(error.line, Some(2));
+
+        assert!(Ledger::parse("day = 1\n").is_err());
+        assert!(Ledger::parse("[[answer]]\ninput = \"unterminated\n").is_err());
+    }
+}
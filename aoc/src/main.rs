@@ -0,0 +1,453 @@
+mod bench;
+mod days;
+mod ledger;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use aoc_common::cli::{usage_error, Args, InputSource};
+use aoc_common::Answer;
+use days::{Day, DAYS};
+use ledger::{hash_input, Entry, Ledger};
+
+const USAGE: &str = "\
+Usage:
+    aoc run --day <day> [--part <part>] [--input <path> | --example]
+    aoc run --all [--part <part>] [--example]
+    aoc verify [--record] [--ledger <path>]
+    aoc bench [--day <day>] [--iterations <n>] [--example] [--json <path>]
+
+Inputs default to day<N>/src/input, so run from the root of the repo. Pass --input - to
+read from stdin, or --example to use the example from the puzzle text.
+
+verify runs every day against its input and checks the answers against answers.toml.
+--record adds any new answers to it (but never overwrites ones that don't match).
+
+bench times parsing and each part separately (every day, unless --day is given), and prints
+the min/median/max. --json writes the timings to a file as well, or - for stdout.";
+
+struct RunArgs {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<InputSource>,
+}
+
+impl RunArgs {
+    fn parse(mut args: Args) -> Result<Self, String> {
+        let day = args.parsed_value("--day")?;
+        let part = args.parsed_value("--part")?;
+        let input = args.value("--input")?;
+        let example = args.flag("--example");
+        let all = args.flag("--all");
+        args.finish()?;
+
+        if !matches!(part, None | Some(1) | Some(2)) {
+            return Err("Part should be 1 or 2".to_string());
+        }
+        let input = match (input, example) {
+            (Some(_), true) => return Err("Can't pass both --input and --example".to_string()),
+            (Some(path), false) => Some(InputSource::from_arg(&path)),
+            (None, true) => Some(InputSource::Example),
+            (None, false) => None,
+        };
+
+        match (all, day, &input) {
+            (true, Some(_), _) => Err("Can't pass both --all and --day".to_string()),
+            (true, None, Some(InputSource::File(_) | InputSource::Stdin)) => {
+                Err("--input only makes sense with --day".to_string())
+            }
+            (false, None, _) => Err("Need either --day or --all".to_string()),
+            _ => Ok(RunArgs { day, part, input }),
+        }
+    }
+
+    fn parts(&self) -> Vec<u8> {
+        match self.part {
+            None => vec![1, 2],
+            Some(part) => vec![part],
+        }
+    }
+
+    fn input_for(&self, day: &Day) -> InputSource {
+        self.input
+            .clone()
+            .unwrap_or_else(|| InputSource::File(day.default_input_path().into()))
+    }
+}
+
+struct Outcome {
+    part: u8,
+    answer: Answer,
+    time_taken: Duration,
+}
+
+// Read and parse the input, then solve the requested parts. The parse time is counted against
+// the first part we run.
+fn run_day(day: &Day, parts: &[u8], source: &InputSource) -> Result<Vec<Outcome>, String> {
+    let start = Instant::now();
+    let parsed = day.load(source)?;
+    let mut parse_time = Some(start.elapsed());
+
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = (day.part(*part))(&parsed);
+            Outcome {
+                part: *part,
+                answer,
+                time_taken: start.elapsed() + parse_time.take().unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+fn print_answer(day: &Day, outcome: &Outcome) {
+    // Multi-line answers (like the CRT screen in day 10) read better starting on their own line.
+    if outcome.answer.is_multiline() {
+        println!(
+            "Day {}, part {} ({:?}):\n{}",
+            day.number, outcome.part, outcome.time_taken, outcome.answer
+        );
+    } else {
+        println!(
+            "Day {}, part {} ({:?}): {}",
+            day.number, outcome.part, outcome.time_taken, outcome.answer
+        );
+    }
+}
+
+fn run_one(args: &RunArgs, number: u8) -> Result<(), String> {
+    let day = days::find(number).ok_or_else(|| format!("No solution for day {}", number))?;
+
+    for outcome in &run_day(day, &args.parts(), &args.input_for(day))? {
+        print_answer(day, outcome);
+    }
+    Ok(())
+}
+
+// One row of the summary table: the day, then a cell for each part.
+struct SummaryRow {
+    day: String,
+    cells: Vec<String>,
+    time_taken: Duration,
+}
+
+fn summary_cell(answer: &Answer) -> String {
+    match answer {
+        Answer::Render(s) => format!("({}-line render)", s.trim_end().lines().count()),
+        _ => answer.to_string(),
+    }
+}
+
+fn print_summary(parts: &[u8], rows: &[SummaryRow]) {
+    let mut header = vec!["Day".to_string()];
+    header.extend(parts.iter().map(|part| format!("Part {}", part)));
+    header.push("Time".to_string());
+
+    let table: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![row.day.clone()];
+            cells.extend(row.cells.iter().cloned());
+            cells.push(format!("{:?}", row.time_taken));
+            cells
+        })
+        .collect();
+
+    print_table(&header, &table);
+}
+
+fn print_table(header: &[String], table: &[Vec<String>]) {
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            table
+                .iter()
+                .map(|cells| cells[i].len())
+                .chain([header[i].len()])
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let print_row = |cells: &[String]| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", padded.join(" | ").trim_end());
+    };
+
+    print_row(header);
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    println!("{}", rule.join("-+-"));
+    for cells in table {
+        print_row(cells);
+    }
+}
+
+fn run_all(args: &RunArgs) {
+    let parts = args.parts();
+    let mut rows = vec![];
+
+    for day in DAYS {
+        let row = match run_day(day, &parts, &args.input_for(day)) {
+            Err(e) => {
+                println!("{}", e);
+                SummaryRow {
+                    day: day.number.to_string(),
+                    cells: parts.iter().map(|_| "(failed)".to_string()).collect(),
+                    time_taken: Duration::ZERO,
+                }
+            }
+            Ok(outcomes) => {
+                for outcome in &outcomes {
+                    print_answer(day, outcome);
+                }
+                SummaryRow {
+                    day: day.number.to_string(),
+                    cells: outcomes.iter().map(|o| summary_cell(&o.answer)).collect(),
+                    time_taken: outcomes.iter().map(|o| o.time_taken).sum(),
+                }
+            }
+        };
+        rows.push(row);
+    }
+
+    println!();
+    print_summary(&parts, &rows);
+}
+
+struct VerifyArgs {
+    record: bool,
+    ledger: PathBuf,
+}
+
+impl VerifyArgs {
+    fn parse(mut args: Args) -> Result<Self, String> {
+        let record = args.flag("--record");
+        let ledger = args
+            .value("--ledger")?
+            .unwrap_or_else(|| "answers.toml".to_string());
+        args.finish()?;
+        Ok(VerifyArgs {
+            record,
+            ledger: ledger.into(),
+        })
+    }
+}
+
+#[derive(Default)]
+struct VerifyCounts {
+    ok: usize,
+    mismatched: usize,
+    new: usize,
+    missing: usize,
+    failed: usize,
+}
+
+fn verify(args: &VerifyArgs) -> Result<(), String> {
+    let mut ledger = Ledger::load(&args.ledger)?;
+    let mut counts = VerifyCounts::default();
+    // Everything in the ledger that we managed to check, so we can complain about the rest.
+    let mut checked = vec![];
+    let mut new_entries = vec![];
+
+    for day in DAYS {
+        let source = InputSource::File(day.default_input_path().into());
+        let outcomes = source
+            .read(day.example)
+            .and_then(|input| Ok((hash_input(&input), run_day(day, &[1, 2], &source)?)));
+        let (input, outcomes) = match outcomes {
+            Ok(result) => result,
+            Err(e) => {
+                println!("FAILED   {}", e);
+                counts.failed += 1;
+                continue;
+            }
+        };
+
+        for outcome in outcomes {
+            let answer = outcome.answer.to_string();
+            match ledger.find(day.number, outcome.part, &input) {
+                Some(entry) if entry.expected == answer => {
+                    counts.ok += 1;
+                    checked.push(entry.clone());
+                }
+                Some(entry) => {
+                    counts.mismatched += 1;
+                    checked.push(entry.clone());
+                    println!(
+                        "MISMATCH Day {}, part {}: expected {:?}, got {:?}",
+                        day.number, outcome.part, entry.expected, answer
+                    );
+                }
+                None => {
+                    counts.new += 1;
+                    println!(
+                        "NEW      Day {}, part {}: {:?}",
+                        day.number, outcome.part, answer
+                    );
+                    new_entries.push(Entry {
+                        day: day.number,
+                        part: outcome.part,
+                        input: input.clone(),
+                        expected: answer,
+                    });
+                }
+            }
+        }
+    }
+
+    // Entries for an input we don't have any more (or a day that failed to run).
+    for entry in ledger.entries.iter().filter(|e| !checked.contains(e)) {
+        counts.missing += 1;
+        println!(
+            "MISSING  Day {}, part {}: nothing checked against input {}",
+            entry.day, entry.part, entry.input
+        );
+    }
+
+    println!(
+        "\n{} ok, {} mismatched, {} new, {} missing, {} failed",
+        counts.ok, counts.mismatched, counts.new, counts.missing, counts.failed
+    );
+
+    if args.record && !new_entries.is_empty() {
+        let recorded = new_entries.len();
+        for entry in new_entries {
+            ledger.add(entry);
+        }
+        ledger.save(&args.ledger)?;
+        println!(
+            "Recorded {} new answers in {}",
+            recorded,
+            args.ledger.display()
+        );
+    }
+
+    if counts.mismatched > 0 || counts.failed > 0 {
+        Err("Verification failed".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+struct BenchArgs {
+    day: Option<u8>,
+    iterations: usize,
+    example: bool,
+    json: Option<String>,
+}
+
+impl BenchArgs {
+    fn parse(mut args: Args) -> Result<Self, String> {
+        let day = args.parsed_value("--day")?;
+        let iterations = args.parsed_value("--iterations")?.unwrap_or(10);
+        let example = args.flag("--example");
+        let json = args.value("--json")?;
+        args.finish()?;
+
+        if iterations == 0 {
+            return Err("Need at least one iteration".to_string());
+        }
+        Ok(BenchArgs {
+            day,
+            iterations,
+            example,
+            json,
+        })
+    }
+}
+
+fn bench(args: &BenchArgs) -> Result<(), String> {
+    let days: Vec<&Day> = match args.day {
+        None => DAYS.iter().collect(),
+        Some(number) => {
+            vec![days::find(number).ok_or_else(|| format!("No solution for day {}", number))?]
+        }
+    };
+    let json_to_stdout = args.json.as_deref() == Some("-");
+
+    let mut results = vec![];
+    for day in days {
+        let source = if args.example {
+            InputSource::Example
+        } else {
+            InputSource::File(day.default_input_path().into())
+        };
+        results.push(bench::bench_day(day, &source, args.iterations)?);
+    }
+
+    let json = bench::to_json(args.iterations, &results);
+    match &args.json {
+        Some(_) if json_to_stdout => {
+            print!("{}", json);
+            return Ok(());
+        }
+        Some(path) => {
+            fs::write(path, json).map_err(|e| format!("Failed to write {}: {}", path, e))?
+        }
+        None => (),
+    }
+
+    let header: Vec<String> = ["Day", "Stage", "Min", "Median", "Max"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let table: Vec<Vec<String>> = results
+        .iter()
+        .flat_map(|result| {
+            result.stages().map(|(stage, stats)| {
+                vec![
+                    result.day.to_string(),
+                    stage.to_string(),
+                    format!("{:?}", stats.min),
+                    format!("{:?}", stats.median),
+                    format!("{:?}", stats.max),
+                ]
+            })
+        })
+        .collect();
+    println!("{} iterations each\n", args.iterations);
+    print_table(&header, &table);
+    Ok(())
+}
+
+fn main() {
+    let mut args = Args::from_env();
+
+    let result = match args.positional().as_deref() {
+        Some("run") => {
+            let run_args = RunArgs::parse(args).unwrap_or_else(|e| usage_error(USAGE, &e));
+            match run_args.day {
+                Some(day) => run_one(&run_args, day),
+                None => {
+                    run_all(&run_args);
+                    Ok(())
+                }
+            }
+        }
+        Some("verify") => {
+            let verify_args = VerifyArgs::parse(args).unwrap_or_else(|e| usage_error(USAGE, &e));
+            verify(&verify_args)
+        }
+        Some("bench") => {
+            let bench_args = BenchArgs::parse(args).unwrap_or_else(|e| usage_error(USAGE, &e));
+            bench(&bench_args)
+        }
+        _ if args.flag("--help") || args.flag("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => usage_error(USAGE, "Expected a command"),
+    };
+
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
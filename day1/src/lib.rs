@@ -0,0 +1,56 @@
+use aoc_common::input::{numbered_chunks, parse_number};
+use aoc_common::{Answer, ParseError, Solution};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    // Total calories carried by each elf, largest first.
+    type Input = Vec<usize>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let mut chunk_sizes: Vec<usize> = vec![];
+        for chunk in numbered_chunks(s) {
+            let mut total = 0;
+            for (line_number, line) in chunk {
+                total += parse_number::<usize>(line, 1).map_err(|e| e.at_line(line_number))?;
+            }
+            chunk_sizes.push(total);
+        }
+
+        chunk_sizes.sort_unstable();
+        chunk_sizes.reverse();
+        Ok(chunk_sizes)
+    }
+
+    // Top elf
+    fn part1(chunk_sizes: &Self::Input) -> Answer {
+        chunk_sizes[0].into()
+    }
+
+    // Top 3 elves
+    fn part2(chunk_sizes: &Self::Input) -> Answer {
+        chunk_sizes[..3].iter().sum::<usize>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day1::parse_example().unwrap();
+        assert_eq!(Day1::part1(&input), Answer::Int(24000));
+        assert_eq!(Day1::part2(&input), Answer::Int(45000));
+    }
+
+    #[test]
+    fn bad_calorie_count() {
+        let error = Day1::parse("1000\n\n20x0\n").unwrap_err();
+        assert_eq!(error.line, Some(3));
+        assert_eq!(error.text, "20x0");
+    }
+}
@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use aoc_common::input::{parse_lines, parse_number};
+use aoc_common::{Answer, ParseError, Solution};
+
+pub enum CPUInstruction {
+    AddX(i64),
+    Noop,
+}
+
+use CPUInstruction::*;
+
+impl FromStr for CPUInstruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "noop" {
+            Ok(Noop)
+        } else if let Some(i) = s.strip_prefix("addx ") {
+            Ok(AddX(parse_number(i, "addx ".len() + 1)?))
+        } else {
+            Err(ParseError::at_column(1, s, "noop or addx <number>"))
+        }
+    }
+}
+
+impl CPUInstruction {
+    fn cost(&self) -> usize {
+        match self {
+            Noop => 1,
+            AddX(_) => 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct State {
+    reg_x: i64,
+}
+
+impl State {
+    fn run(&mut self, instruction: &CPUInstruction) {
+        match instruction {
+            Noop => (),
+            AddX(i) => self.reg_x += i,
+        }
+    }
+}
+
+// Returns the state DURING the given clock cycle. So the first state is 0.
+fn run_instructions(state: &mut State, instructions: &[CPUInstruction]) -> Vec<State> {
+    let mut result = vec![state.clone()];
+
+    for instruction in instructions {
+        for _ in 0..instruction.cost() {
+            result.push(state.clone());
+        }
+        state.run(instruction);
+    }
+    result
+}
+
+const SCREEN_WIDTH: usize = 40;
+const SCREEN_HEIGHT: usize = 6;
+
+fn draw_output(output_states: &[State]) -> String {
+    assert!(output_states.len() == SCREEN_WIDTH * SCREEN_HEIGHT);
+
+    let mut s = String::new();
+    for row in 0..SCREEN_HEIGHT {
+        for pixel in 0..SCREEN_WIDTH {
+            let sprite_centre: i64 = output_states[row * SCREEN_WIDTH + pixel].reg_x;
+            let distance_from_sprite_centre = (pixel as i64) - sprite_centre;
+
+            if distance_from_sprite_centre.abs() <= 1 {
+                s.push('#');
+            } else {
+                s.push('.');
+            }
+        }
+        s.push('\n');
+    }
+    s
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Vec<CPUInstruction>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(s)
+    }
+
+    fn part1(instructions: &Self::Input) -> Answer {
+        let output_states = run_instructions(&mut State { reg_x: 1 }, instructions);
+
+        let sum_of_signal_strengths_at_interesting_points: i64 = [20, 60, 100, 140, 180, 220]
+            .iter()
+            .copied()
+            .map(|x| (x as i64) * output_states[x].reg_x)
+            .sum();
+
+        sum_of_signal_strengths_at_interesting_points.into()
+    }
+
+    fn part2(instructions: &Self::Input) -> Answer {
+        let output_states = run_instructions(&mut State { reg_x: 1 }, instructions);
+        Answer::Render(draw_output(&output_states[1..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day10::parse_example().unwrap();
+        assert_eq!(Day10::part1(&input), Answer::Int(13140));
+
+        let expected = "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+";
+        assert_eq!(Day10::part2(&input), Answer::Render(expected.to_string()));
+    }
+
+    #[test]
+    fn small_program() {
+        let instructions = Day10::parse("noop\naddx 3\naddx -5\n").unwrap();
+        let states = run_instructions(&mut State { reg_x: 1 }, &instructions);
+        let reg_x: Vec<i64> = states.iter().map(|state| state.reg_x).collect();
+        assert_eq!(reg_x, vec![1, 1, 1, 1, 4, 4]);
+    }
+}
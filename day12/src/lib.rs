@@ -0,0 +1,138 @@
+use aoc_common::graph::{self, GridGraph};
+use aoc_common::grid::{Grid, Pos};
+use aoc_common::{Answer, ParseError, Solution};
+
+#[derive(Debug)]
+struct Heights(Grid<usize>);
+
+impl Heights {
+    // We can only climb one step at a time, but can drop any amount.
+    fn graph(&self) -> GridGraph<'_, usize, impl Fn(&usize, &usize) -> bool> {
+        GridGraph::new(&self.0, |from, to| *to <= *from + 1)
+    }
+
+    fn shortest_climb(&self, starts: impl IntoIterator<Item = Pos>, end: Pos) -> Option<usize> {
+        graph::multi_source_bfs(&self.graph(), starts).distance(end)
+    }
+}
+
+#[derive(Debug)]
+pub struct Heightmap {
+    heights: Heights,
+    start: Pos,
+    end: Pos,
+    a_points: Vec<Pos>,
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u8 = 12;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Heightmap;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let map = Grid::from_char_map(s)?;
+
+        let find_all_points_with_char = |target_char| {
+            map.iter()
+                .filter(|(_, c)| **c == target_char)
+                .map(|(p, _)| p)
+                .collect::<Vec<Pos>>()
+        };
+
+        let find_char = |target_char| {
+            let v = find_all_points_with_char(target_char);
+            let expected = format!("exactly one {}", target_char);
+            match v[..] {
+                [] => Err(ParseError::new("", expected)),
+                [point] => Ok(point),
+                [_, (row, column), ..] => {
+                    Err(ParseError::at_column(column + 1, target_char, expected).at_line(row + 1))
+                }
+            }
+        };
+
+        let start_char = 'S';
+        let end_char = 'E';
+
+        let start = find_char(start_char)?;
+        let end = find_char(end_char)?;
+
+        let char_to_score = |char: &char| {
+            if *char == start_char {
+                'a' as usize
+            } else if *char == end_char {
+                'z' as usize
+            } else {
+                *char as usize
+            }
+        };
+
+        let heights = Heights(map.map(char_to_score));
+        let a_points = find_all_points_with_char('a');
+        Ok(Heightmap {
+            heights,
+            start,
+            end,
+            a_points,
+        })
+    }
+
+    fn part1(heightmap: &Heightmap) -> Answer {
+        heightmap
+            .heights
+            .shortest_climb([heightmap.start], heightmap.end)
+            .expect("No path from S to E")
+            .into()
+    }
+
+    fn part2(heightmap: &Heightmap) -> Answer {
+        // Searching from every 'a' at once finds the distance from whichever is closest.
+        let best_path_from_any_a_point = heightmap
+            .heights
+            .shortest_climb(heightmap.a_points.iter().copied(), heightmap.end)
+            .expect("No path from any a point to E");
+        best_path_from_any_a_point.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day12::parse_example().unwrap();
+        assert_eq!(Day12::part1(&input), Answer::Int(31));
+        assert_eq!(Day12::part2(&input), Answer::Int(29));
+    }
+
+    #[test]
+    fn can_only_climb_one_step_at_a_time() {
+        let heightmap = Day12::parse("SbcE\n").unwrap();
+        assert_eq!(
+            heightmap
+                .heights
+                .shortest_climb([heightmap.start], heightmap.end),
+            None
+        );
+
+        // ...so we have to go the long way round.
+        let heightmap = Day12::parse("Sbcdefghijklm\nEyxwvutsrqpon\n").unwrap();
+        assert_eq!(
+            heightmap
+                .heights
+                .shortest_climb([heightmap.start], heightmap.end),
+            Some(25)
+        );
+    }
+
+    #[test]
+    fn start_and_end_must_be_unique() {
+        let error = Day12::parse("SabE\nabSc\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        assert!(Day12::parse("Sabc\n").is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use std::cmp::Ordering::*;
+use std::str::FromStr;
+
+use aoc_common::input::{numbered_lines, parse_number};
+use aoc_common::{Answer, ParseError, Solution};
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Sexp {
+    Atom(usize),
+    List(Vec<Sexp>),
+}
+use Sexp::*;
+
+impl FromStr for Sexp {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = SexpParser { s, pos: 0 };
+        let sexp = parser.sexp()?;
+        if parser.pos == s.len() {
+            Ok(sexp)
+        } else {
+            Err(parser.error("the end of the packet"))
+        }
+    }
+}
+
+// Recursive descent over a single line, keeping track of where we are for error messages.
+struct SexpParser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl SexpParser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    fn error(&self, expected: &str) -> ParseError {
+        let found = self.peek().map(String::from).unwrap_or_default();
+        ParseError::at_column(self.pos + 1, found, expected)
+    }
+
+    fn sexp(&mut self) -> Result<Sexp, ParseError> {
+        match self.peek() {
+            Some('[') => {
+                self.pos += 1;
+                let mut elements = vec![];
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                    return Ok(List(elements));
+                }
+                loop {
+                    elements.push(self.sexp()?);
+                    match self.peek() {
+                        Some(',') => self.pos += 1,
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(List(elements));
+                        }
+                        _ => return Err(self.error("',' or ']'")),
+                    }
+                }
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let start = self.pos;
+                let digits = self.s[start..]
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .count();
+                self.pos += digits;
+                Ok(Atom(parse_number(&self.s[start..self.pos], start + 1)?))
+            }
+            _ => Err(self.error("a number or '['")),
+        }
+    }
+}
+
+impl Sexp {
+    fn compare(&self, other: &Self) -> std::cmp::Ordering {
+        fn compare_slices(x: &[Sexp], y: &[Sexp]) -> std::cmp::Ordering {
+            match (x.is_empty(), y.is_empty()) {
+                (true, true) => Equal,
+                (true, false) => Less,
+                (false, true) => Greater,
+                (false, false) => match x[0].compare(&y[0]) {
+                    Less => Less,
+                    Greater => Greater,
+                    Equal => compare_slices(&x[1..], &y[1..]),
+                },
+            }
+        }
+
+        match (self, other) {
+            (Atom(x), Atom(y)) => x.cmp(y),
+            (Atom(x), List(_)) => List(vec![Atom(*x)]).compare(other),
+            (List(_), Atom(y)) => self.compare(&List(vec![Atom(*y)])),
+            (List(x), List(y)) => compare_slices(x, y),
+        }
+    }
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u8 = 13;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Vec<(Sexp, Sexp)>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let parse_line = |(line_number, line): &(usize, &str)| {
+            line.parse::<Sexp>().map_err(|e| e.at_line(*line_number))
+        };
+
+        numbered_lines(s)
+            .chunks(2)
+            .map(|pair| match pair {
+                [first, second] => Ok((parse_line(first)?, parse_line(second)?)),
+                _ => Err(ParseError::new(
+                    "",
+                    "a second packet to pair with the last one",
+                )),
+            })
+            .collect()
+    }
+
+    fn part1(sexps: &Self::Input) -> Answer {
+        let result: usize = sexps
+            .iter()
+            .enumerate()
+            .flat_map(|(i, pair)| {
+                if pair.0.compare(&pair.1) == Less {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            })
+            .sum();
+        result.into()
+    }
+
+    fn part2(sexps: &Self::Input) -> Answer {
+        let mut all_sexps: Vec<Sexp> = sexps
+            .iter()
+            .flat_map(|s| vec![s.0.clone(), s.1.clone()])
+            .collect();
+        let divider0 = List(vec![List(vec![Atom(2)])]);
+        let divider1 = List(vec![List(vec![Atom(6)])]);
+        all_sexps.push(divider0.clone());
+        all_sexps.push(divider1.clone());
+
+        all_sexps.sort_by(Sexp::compare);
+
+        let index0 = all_sexps
+            .binary_search_by(|s| s.compare(&divider0))
+            .unwrap()
+            + 1;
+        let index1 = all_sexps
+            .binary_search_by(|s| s.compare(&divider1))
+            .unwrap()
+            + 1;
+        (index0 * index1).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sexp(s: &str) -> Sexp {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn example() {
+        let input = Day13::parse_example().unwrap();
+        assert_eq!(Day13::part1(&input), Answer::Int(13));
+        assert_eq!(Day13::part2(&input), Answer::Int(140));
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(sexp("[1,1,3,1,1]").compare(&sexp("[1,1,5,1,1]")), Less);
+        // An atom compares as a list containing just that atom.
+        assert_eq!(sexp("[[1],[2,3,4]]").compare(&sexp("[[1],4]")), Less);
+        assert_eq!(sexp("[9]").compare(&sexp("[[8,7,6]]")), Greater);
+        assert_eq!(sexp("[[[]]]").compare(&sexp("[[]]")), Greater);
+        assert_eq!(sexp("[7,7,7,7]").compare(&sexp("[7,7,7]")), Greater);
+        assert_eq!(sexp("[]").compare(&sexp("[3]")), Less);
+        assert_eq!(sexp("[2]").compare(&sexp("2")), Equal);
+        assert_eq!(sexp("[[2]]").compare(&sexp("[2]")), Equal);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(sexp("[10,[]]"), List(vec![Atom(10), List(vec![])]));
+
+        let error = "[1,,2]".parse::<Sexp>().unwrap_err();
+        assert_eq!(error.column, Some(4));
+        assert!("[1,2".parse::<Sexp>().is_err());
+        assert!("[1]]".parse::<Sexp>().is_err());
+        assert!(Day13::parse("[1]\n[2]\n\n[3]\n").is_err());
+    }
+}
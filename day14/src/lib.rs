@@ -0,0 +1,249 @@
+use aoc_common::grid::SparseGrid;
+use aoc_common::input::{numbered_lines, parse_number};
+use aoc_common::point::Point2;
+use aoc_common::{Answer, ParseError, Solution};
+
+type Point = Point2<i64>;
+
+#[derive(Clone, Debug)]
+enum Tile {
+    Rock,
+    Sand,
+}
+
+#[derive(Clone, Debug)]
+pub struct Cave(SparseGrid<Tile>);
+
+impl Cave {
+    fn next_falling_point(&self, point: &Point) -> Option<Point> {
+        [point.x, point.x - 1, point.x + 1]
+            .into_iter()
+            .map(|x| Point::new(x, point.y + 1))
+            .find(|p| !self.0.contains(p.row_column()))
+    }
+
+    fn add_sand(&mut self, point: &Point) {
+        let _ = self.0.insert(point.row_column(), Tile::Sand);
+    }
+
+    fn add_line_exn(&mut self, point0: &Point, point1: &Point) {
+        let Point2 { x: x0, y: y0 } = *point0;
+        let Point2 { x: x1, y: y1 } = *point1;
+
+        let get_range = |i0: i64, i1: i64| {
+            let i_start = i0.min(i1);
+            let i_end = i0.max(i1);
+            i_start..=i_end
+        };
+
+        if x0 == x1 {
+            for y in get_range(y0, y1) {
+                self.0.insert(Point::new(x0, y).row_column(), Tile::Rock);
+            }
+        } else if y0 == y1 {
+            for x in get_range(x0, x1) {
+                self.0.insert(Point::new(x, y0).row_column(), Tile::Rock);
+            }
+        } else {
+            panic!("Can't call [add_line] unless x/y co-ordinates line up.")
+        }
+    }
+
+    fn largest_y_coord(&self) -> i64 {
+        self.0.bounds().map_or(0, |(_, (max_row, _))| max_row)
+    }
+
+    // Drawn the same way as the puzzle text, but only covering the bits with something in them.
+    pub fn render(&self) -> String {
+        self.0.render(|tile| match tile {
+            None => '.',
+            Some(Tile::Rock) => '#',
+            Some(Tile::Sand) => 'o',
+        })
+    }
+}
+
+const SAND_START: Point = Point::new(500, 0);
+
+// A point like "498,4", starting at [column] of the line.
+fn parse_point(s: &str, column: usize) -> Result<Point, ParseError> {
+    let (x, y) = s
+        .split_once(',')
+        .ok_or_else(|| ParseError::at_column(column, s, "a point like 498,4"))?;
+    Ok(Point::new(
+        parse_number(x, column)?,
+        parse_number(y, column + x.len() + 1)?,
+    ))
+}
+
+fn parse_rocks(s: &str) -> Result<Cave, ParseError> {
+    let mut grid = Cave(SparseGrid::new());
+
+    for (line_number, line) in numbered_lines(s) {
+        let mut points: Vec<(Point, usize)> = vec![];
+        let mut column = 1;
+        for point in line.split(" -> ") {
+            let parsed = parse_point(point, column).map_err(|e| e.at_line(line_number))?;
+            points.push((parsed, column));
+            column += point.len() + " -> ".len();
+        }
+
+        for pair in points.windows(2) {
+            let ((p0, _), (p1, column)) = (pair[0], pair[1]);
+            if p0.x != p1.x && p0.y != p1.y {
+                let text = format!("{},{}", p1.x, p1.y);
+                let expected = "a point in line with the one before";
+                return Err(ParseError::at_column(column, text, expected).at_line(line_number));
+            }
+            grid.add_line_exn(&p0, &p1);
+        }
+    }
+    Ok(grid)
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u8 = 14;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Cave;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse_rocks(s)
+    }
+
+    fn part1(grid: &Cave) -> Answer {
+        let mut grid = grid.clone();
+        let abyss_level = grid.largest_y_coord() + 1;
+
+        let mut sand_count_rested: usize = 0;
+        loop {
+            let mut sand_point = SAND_START;
+            let mut sand_landed = false;
+
+            while !sand_landed && sand_point.y < abyss_level {
+                match grid.next_falling_point(&sand_point) {
+                    None => {
+                        sand_landed = true;
+                    }
+                    Some(new_point) => sand_point = new_point,
+                }
+            }
+
+            if sand_point.y == abyss_level {
+                break;
+            } else {
+                sand_count_rested += 1;
+                grid.add_sand(&sand_point);
+            }
+        }
+        sand_count_rested.into()
+    }
+
+    fn part2(grid: &Cave) -> Answer {
+        // EG: I'm not thrilled with the code duplication here, I think I could probably
+        // fold this all into a function that takes in some sort of stop condition.
+        // (Also, it's a bit lazy just to add a really long line for the floor.)
+        let mut grid = grid.clone();
+        let floor_level = grid.largest_y_coord() + 2;
+        let floor = (
+            Point::new(-100_000, floor_level),
+            Point::new(100_000, floor_level),
+        );
+        grid.add_line_exn(&floor.0, &floor.1);
+
+        let mut sand_count_rested: usize = 0;
+        loop {
+            let mut sand_point = SAND_START;
+            let mut sand_landed = false;
+
+            while !sand_landed {
+                match grid.next_falling_point(&sand_point) {
+                    None => {
+                        sand_landed = true;
+                    }
+                    Some(new_point) => sand_point = new_point,
+                }
+            }
+
+            sand_count_rested += 1;
+            grid.add_sand(&sand_point);
+            if sand_point == SAND_START {
+                break;
+            }
+        }
+        sand_count_rested.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day14::parse_example().unwrap();
+        assert_eq!(Day14::part1(&input), Answer::Int(24));
+        assert_eq!(Day14::part2(&input), Answer::Int(93));
+    }
+
+    #[test]
+    fn sand_prefers_down_then_left_then_right() {
+        let mut grid = parse_rocks("498,2 -> 502,2\n").unwrap();
+        assert_eq!(
+            grid.next_falling_point(&Point::new(500, 0)),
+            Some(Point::new(500, 1))
+        );
+
+        grid.add_sand(&Point::new(500, 1));
+        assert_eq!(
+            grid.next_falling_point(&Point::new(500, 0)),
+            Some(Point::new(499, 1))
+        );
+
+        grid.add_sand(&Point::new(499, 1));
+        assert_eq!(
+            grid.next_falling_point(&Point::new(500, 0)),
+            Some(Point::new(501, 1))
+        );
+
+        grid.add_sand(&Point::new(501, 1));
+        assert!(grid.next_falling_point(&Point::new(500, 0)).is_none());
+    }
+
+    #[test]
+    fn render() {
+        let cave = Day14::parse_example().unwrap();
+        let expected = "\
+....#...##
+....#...#.
+..###...#.
+........#.
+........#.
+#########.
+";
+        assert_eq!(cave.render(), expected);
+    }
+
+    #[test]
+    fn bad_rock_paths() {
+        let error = |s: &str| Day14::parse(s).unwrap_err().to_string();
+        assert_eq!(
+            error("498,4 -> 498,6\n503,4 -> 50x,4\n"),
+            "line 2, column 10: expected a number, found \"50x\""
+        );
+        assert_eq!(
+            error("498,4 -> 498;6\n"),
+            "line 1, column 10: expected a point like 498,4, found \"498;6\""
+        );
+        assert_eq!(
+            error("498,4 -> 498,6 -> 500,8\n"),
+            "line 1, column 19: expected a point in line with the one before, found \"500,8\""
+        );
+        assert_eq!(
+            error("498,4 -> 498,6,7\n"),
+            "line 1, column 14: expected a number, found \"6,7\""
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use aoc_common::input::{extract_ints, parse_lines_with};
+use aoc_common::intervals::{self, IntervalSet};
+use aoc_common::point::Point2;
+use aoc_common::{Answer, ParseError, Solution};
+
+type Position = Point2<i64>;
+type Interval = intervals::Interval<i64>;
+
+fn find_range_with_no_beacon(
+    sensor: &Position,
+    beacon: &Position,
+    y_value: i64,
+) -> Option<Interval> {
+    let beacon_distance = sensor.manhattan_distance(beacon);
+    let sensor_projected_to_y_axis = Position::new(sensor.x, y_value);
+    let distance_to_y_axis = sensor.manhattan_distance(&sensor_projected_to_y_axis);
+
+    if distance_to_y_axis > beacon_distance {
+        // We learn nothing - the range around the sensor doesn't intersect the y-axis.
+        None
+    } else {
+        // point1 and point2 are the two points on the y-axis, the same distance from the sensor
+        // as the beacon. (We possibly have a degenerate case where they're equal.)
+        let mut point1 = sensor.x - (beacon_distance - distance_to_y_axis);
+        let mut point2 = sensor.x + (beacon_distance - distance_to_y_axis);
+
+        // If either one is the beacon, we don't want to include those in our interval.
+        if beacon.y == y_value {
+            if beacon.x == point1 {
+                point1 += 1
+            }
+            if beacon.x == point2 {
+                point2 -= 1
+            }
+        }
+
+        // Then turn this into the interval!
+        if point1 <= point2 {
+            Some(Interval::new(point1, point2))
+        } else {
+            None
+        }
+    }
+}
+
+fn find_intervals_with_no_beacon(
+    sensor_beacon_pairs: &[(Position, Position)],
+    y_value: i64,
+    include_known_beacons_in_result: bool,
+) -> IntervalSet<i64> {
+    let mut intervals: Vec<Interval> = sensor_beacon_pairs
+        .iter()
+        .filter_map(|(sensor, beacon)| find_range_with_no_beacon(sensor, beacon, y_value))
+        .collect();
+
+    if include_known_beacons_in_result {
+        for (_sensor, beacon) in sensor_beacon_pairs {
+            if beacon.y == y_value {
+                intervals.push(Interval::single(beacon.x));
+            }
+        }
+    }
+    intervals.into_iter().collect()
+}
+
+pub struct SensorReport {
+    sensor_and_beacons: Vec<(Position, Position)>,
+    // The row we're interested in for part 1
+    row: i64,
+    // The distress beacon in part 2 has x and y co-ordinates in [0, bound]
+    bound: i64,
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u8 = 15;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = SensorReport;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let parse_line = |s: &str| -> Result<(Position, Position), ParseError> {
+            let numbers = extract_ints(s);
+            if !(s.starts_with("Sensor at") && s.contains(": closest beacon is at"))
+                || numbers.len() != 4
+            {
+                return Err(ParseError::at_column(
+                    1,
+                    s,
+                    "Sensor at x=<x>, y=<y>: closest beacon is at x=<x>, y=<y>",
+                ));
+            }
+
+            let sensor = Position::new(numbers[0], numbers[1]);
+            let beacon = Position::new(numbers[2], numbers[3]);
+            Ok((sensor, beacon))
+        };
+
+        Ok(SensorReport {
+            sensor_and_beacons: parse_lines_with(s, parse_line)?,
+            row: 2_000_000,
+            bound: 4_000_000,
+        })
+    }
+
+    // The example asks about a smaller area than the real thing.
+    fn parse_example() -> Result<Self::Input, ParseError> {
+        Ok(SensorReport {
+            row: 10,
+            bound: 20,
+            ..Self::parse(Self::EXAMPLE)?
+        })
+    }
+
+    fn part1(report: &SensorReport) -> Answer {
+        let no_beacon =
+            find_intervals_with_no_beacon(&report.sensor_and_beacons, report.row, false);
+
+        no_beacon.len().into()
+    }
+
+    fn part2(report: &SensorReport) -> Answer {
+        // I'm just running my solution for part 1 over all possible y-values, and then seeing which one doesn't cover the whole interval.
+        // I suspect there's a faster way to do this, but I'm not seeing it.
+        let search_area = Interval::new(0, report.bound);
+        let (x, y) = (0..=report.bound)
+            .find_map(|y| {
+                let gap = find_intervals_with_no_beacon(&report.sensor_and_beacons, y, true)
+                    .gaps(search_area)
+                    .next()?;
+                Some((gap.start, y))
+            })
+            .expect("No gap for the distress beacon anywhere in the search area");
+
+        // The puzzle wants the "tuning frequency" rather than the co-ordinates.
+        (x * 4_000_000 + y).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day15::parse_example().unwrap();
+        assert_eq!(Day15::part1(&input), Answer::Int(26));
+        // The only gap is at x=14, y=11.
+        assert_eq!(Day15::part2(&input), Answer::Int(56000011));
+    }
+
+    #[test]
+    fn no_beacon_intervals() {
+        let report = Day15::parse_example().unwrap();
+        let sensors = &report.sensor_and_beacons;
+
+        let row_10 = find_intervals_with_no_beacon(sensors, 10, false);
+        assert_eq!(
+            row_10.intervals(),
+            &[Interval::new(-2, 1), Interval::new(3, 24)]
+        );
+
+        let row_11 = find_intervals_with_no_beacon(sensors, 11, true);
+        assert_eq!(
+            row_11.intervals(),
+            &[Interval::new(-3, 13), Interval::new(15, 25)]
+        );
+    }
+
+    #[test]
+    fn no_beacon_range() {
+        // This sensor reaches 9 away, so on y=10 it covers x=2..=14, except for the beacon itself.
+        let sensor = Position::new(8, 7);
+        let beacon = Position::new(2, 10);
+        assert_eq!(
+            find_range_with_no_beacon(&sensor, &beacon, 10),
+            Some(Interval::new(3, 14))
+        );
+        assert_eq!(
+            find_range_with_no_beacon(&sensor, &beacon, 7),
+            Some(Interval::new(-1, 17))
+        );
+        assert_eq!(find_range_with_no_beacon(&sensor, &beacon, 17), None);
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::fmt;
-use std::fs::read_to_string;
+
+use aoc_common::graph::AdjacencyMatrix;
+use aoc_common::input::{lines, read_input};
 
 const STARTING_LABEL: &str = "AA";
 
@@ -8,7 +10,7 @@ const STARTING_LABEL: &str = "AA";
 struct Graph {
     label_to_indices: HashMap<String, usize>,
     flow_rates: Vec<usize>,
-    edges: Vec<Vec<Option<usize>>>,
+    edges: AdjacencyMatrix,
 }
 
 impl fmt::Debug for Graph {
@@ -17,48 +19,16 @@ impl fmt::Debug for Graph {
             writeln!(f, "{}: {}", s, i)?;
         }
         writeln!(f, "Edges:")?;
-        for (i, edge_row) in self.edges.iter().enumerate() {
-            writeln!(f, "{}: {:?}", i, edge_row)?;
+        for i in 0..self.edges.len() {
+            writeln!(f, "{}: {:?}", i, self.edges.row(i))?;
         }
         Ok(())
     }
 }
 
 impl Graph {
-    // Run Floyd-Marshall on a graph
-    fn floyd_marshall(&self) -> Vec<Vec<Option<usize>>> {
-        let mut matrix: Vec<Vec<Option<usize>>> = self.edges.clone();
-
-        for i in 0..self.edges.len() {
-            // Allow considering paths that go through vertex i.
-            // So up to this point, we'll have paths that allow vertices 0, ..., i-1.
-            for j in 0..self.edges.len() {
-                for k in 0..self.edges.len() {
-                    // Is there a path from j -> k, going through vertex i?
-                    match (matrix[j][i], matrix[i][k]) {
-                        (None, _) | (_, None) => (),
-                        (Some(x1), Some(x2)) => {
-                            let best_path_going_through_vertex = x1 + x2;
-
-                            matrix[j][k] = {
-                                match matrix[j][k] {
-                                    None => Some(best_path_going_through_vertex),
-                                    Some(current) => {
-                                        Some(current.min(best_path_going_through_vertex))
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        matrix
-    }
-
     fn compress_empty_flow_rates(&self) -> Graph {
-        let matrix_using_old_indices = self.floyd_marshall();
+        let all_pairs_using_old_indices = self.edges.floyd_warshall();
 
         let keep = |i: usize| self.flow_rates[i] != 0 || self.label_to_indices[STARTING_LABEL] == i;
 
@@ -86,15 +56,19 @@ impl Graph {
             })
             .collect();
 
-        let mut edges: Vec<Vec<Option<usize>>> = vec![];
+        let mut edges = AdjacencyMatrix::new(label_to_indices.len());
         for i in 0..label_to_indices.len() {
-            let mut row: Vec<Option<usize>> = Vec::new();
-            for j in 0..label_to_indices.len() {
+            // [floyd_warshall] says every valve is 0 away from itself, where my old version gave
+            // the shortest loop back to it. Neither's any use here, since we never walk from a
+            // valve to itself (it's either already open, or we'd open it without moving), so
+            // leave those edges out rather than having free moves in the graph.
+            for j in (0..label_to_indices.len()).filter(|j| *j != i) {
                 let old_i = new_to_old_index.get(&i).unwrap();
                 let old_j = new_to_old_index.get(&j).unwrap();
-                row.push(matrix_using_old_indices[*old_i][*old_j]);
+                if let Some(distance) = all_pairs_using_old_indices.distance(*old_i, *old_j) {
+                    edges.add_edge(i, j, distance);
+                }
             }
-            edges.push(row);
         }
         Graph {
             label_to_indices,
@@ -105,12 +79,8 @@ impl Graph {
 }
 
 fn main() {
-    let input: Vec<String> = read_to_string("input")
-        .expect("Failed to read input file")
-        .split('\n')
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect();
+    let s = read_input("input").expect("Failed to read input file");
+    let input: Vec<String> = lines(&s).into_iter().map(|s| s.to_string()).collect();
 
     // Parsing junk. I'm so bad at this in Rust!
     let parsed: Vec<(String, usize, Vec<String>)> = input
@@ -128,7 +98,7 @@ fn main() {
                 (before.parse::<usize>().unwrap(), after)
             };
             let s = {
-                vec![" tunnel leads to valve ", " tunnels lead to valves "]
+                [" tunnel leads to valve ", " tunnels lead to valves "]
                     .iter()
                     .find_map(|prefix| s.strip_prefix(prefix))
                     .unwrap()
@@ -149,15 +119,12 @@ fn main() {
         .map(|(_name, flow_rate, _edges)| *flow_rate)
         .collect();
 
-    let mut edges: Vec<Vec<Option<usize>>> = vec![];
-    for _ in 0..label_to_indices.len() {
-        edges.push(vec![None; label_to_indices.len()]);
-    }
+    let mut edges = AdjacencyMatrix::new(label_to_indices.len());
     for (name, _, valves) in &parsed {
         let i = label_to_indices.get(name).unwrap();
         for valve in valves {
             let j = label_to_indices.get(valve).unwrap();
-            edges[*i][*j] = Some(1);
+            edges.add_edge(*i, *j, 1);
         }
     }
 
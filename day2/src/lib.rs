@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use aoc_common::input::parse_lines_with;
+use aoc_common::{Answer, ParseError, Solution};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Move {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Outcome {
+    Win,
+    Lose,
+    Draw,
+}
+use crate::Move::*;
+use crate::Outcome::*;
+
+impl Move {
+    fn wins_against(&self, other: &Move) -> Outcome {
+        match (self, &other) {
+            (Rock, Rock) => Draw,
+            (Paper, Paper) => Draw,
+            (Scissors, Scissors) => Draw,
+            (Rock, Scissors) => Win,
+            (Scissors, Paper) => Win,
+            (Paper, Rock) => Win,
+            (Scissors, Rock) => Lose,
+            (Paper, Scissors) => Lose,
+            (Rock, Paper) => Lose,
+        }
+    }
+
+    fn find_move_that_gives_result_against_this(&self, outcome: &Outcome) -> Move {
+        vec![Rock, Paper, Scissors]
+            .into_iter()
+            .find(|other_move| {
+                let outcome_with_this = other_move.wins_against(self);
+                *outcome == outcome_with_this
+            })
+            .expect("BUG, couldn't find move that gave outcome")
+    }
+}
+
+// The opponent's move, from the first column of the strategy guide.
+impl FromStr for Move {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "A" => Ok(Rock),
+            "B" => Ok(Paper),
+            "C" => Ok(Scissors),
+            _ => Err(ParseError::at_column(1, s, "A, B or C")),
+        }
+    }
+}
+
+// The second column of the strategy guide. What it means depends on which part we're doing.
+#[derive(Clone, Copy, Debug)]
+pub enum Code {
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Code {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X" => Ok(Code::X),
+            "Y" => Ok(Code::Y),
+            "Z" => Ok(Code::Z),
+            _ => Err(ParseError::at_column(1, s, "X, Y or Z")),
+        }
+    }
+}
+
+// For part 1
+fn your_move(code: Code) -> Move {
+    match code {
+        Code::X => Rock,
+        Code::Y => Paper,
+        Code::Z => Scissors,
+    }
+}
+
+// For part 2
+fn intended_result(code: Code) -> Outcome {
+    match code {
+        Code::X => Lose,
+        Code::Y => Draw,
+        Code::Z => Win,
+    }
+}
+
+fn parse_round(s: &str) -> Result<(Move, Code), ParseError> {
+    let (first, second) = s
+        .split_once(' ')
+        .ok_or_else(|| ParseError::at_column(1, s, "two columns separated by a space"))?;
+    let opponent_move = first.parse()?;
+    let code = second
+        .parse()
+        .map_err(|e: ParseError| e.shift_columns(first.len() + 1))?;
+    Ok((opponent_move, code))
+}
+
+fn get_score(your_move: &Move, opponent_move: &Move) -> usize {
+    let outcome = your_move.wins_against(opponent_move);
+
+    let move_score = match your_move {
+        Rock => 1,
+        Paper => 2,
+        Scissors => 3,
+    };
+
+    let win_score = match outcome {
+        Lose => 0,
+        Draw => 3,
+        Win => 6,
+    };
+
+    move_score + win_score
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Vec<(Move, Code)>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse_lines_with(s, parse_round)
+    }
+
+    fn part1(guide: &Self::Input) -> Answer {
+        let score: usize = guide
+            .iter()
+            .map(|(opponent_move, code)| get_score(&your_move(*code), opponent_move))
+            .sum();
+
+        score.into()
+    }
+
+    fn part2(guide: &Self::Input) -> Answer {
+        let score: usize = guide
+            .iter()
+            .map(|(opponent_move, code)| {
+                let intended_outcome = intended_result(*code);
+                let your_move =
+                    opponent_move.find_move_that_gives_result_against_this(&intended_outcome);
+                get_score(&your_move, opponent_move)
+            })
+            .sum();
+
+        score.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day2::parse_example().unwrap();
+        assert_eq!(Day2::part1(&input), Answer::Int(15));
+        assert_eq!(Day2::part2(&input), Answer::Int(12));
+    }
+
+    #[test]
+    fn wins_against() {
+        assert_eq!(Rock.wins_against(&Scissors), Win);
+        assert_eq!(Scissors.wins_against(&Paper), Win);
+        assert_eq!(Paper.wins_against(&Rock), Win);
+        assert_eq!(Rock.wins_against(&Paper), Lose);
+        assert_eq!(Paper.wins_against(&Paper), Draw);
+    }
+
+    #[test]
+    fn move_for_outcome() {
+        assert_eq!(Rock.find_move_that_gives_result_against_this(&Win), Paper);
+        assert_eq!(
+            Rock.find_move_that_gives_result_against_this(&Lose),
+            Scissors
+        );
+        assert_eq!(
+            Scissors.find_move_that_gives_result_against_this(&Draw),
+            Scissors
+        );
+    }
+
+    #[test]
+    fn bad_code_points_at_second_column() {
+        let error = Day2::parse("A Y\nB Q\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        assert_eq!(error.expected, "X, Y or Z");
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use aoc_common::input::numbered_lines;
+use aoc_common::{Answer, ParseError, Solution};
+
+fn char_priority(c: char) -> usize {
+    if c.is_ascii_uppercase() {
+        (c as usize) - 0x41 + 27
+    } else if c.is_ascii_lowercase() {
+        (c as usize) - 0x61 + 1
+    } else {
+        panic!("can't parse priority of char {}, not alphabet", c)
+    }
+}
+
+// The one item in [s], or an error saying what was there instead (in order, so that the error
+// is the same every time).
+fn unique_item(s: HashSet<char>, expected: &str) -> Result<char, ParseError> {
+    let mut items: Vec<char> = s.into_iter().collect();
+    if items.len() != 1 {
+        items.sort();
+        return Err(ParseError::new(String::from_iter(items), expected));
+    }
+    Ok(items[0])
+}
+
+#[derive(Debug)]
+pub struct Rucksack {
+    first_half: HashSet<char>,
+    second_half: HashSet<char>,
+    // The item that's in both halves.
+    shared: char,
+}
+
+impl FromStr for Rucksack {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        if let Some((i, c)) = line
+            .chars()
+            .enumerate()
+            .find(|(_, c)| !c.is_ascii_alphabetic())
+        {
+            return Err(ParseError::at_column(i + 1, c, "an item (a letter)"));
+        }
+        if !line.len().is_multiple_of(2) {
+            return Err(ParseError::at_column(
+                1,
+                line,
+                "an even number of items, to split between the two compartments",
+            ));
+        }
+
+        let first_half = line[0..line.len() / 2].chars().collect::<HashSet<char>>();
+        let second_half = line[line.len() / 2..].chars().collect::<HashSet<char>>();
+        let shared = unique_item(
+            &first_half & &second_half,
+            "exactly one item in both compartments",
+        )?;
+        Ok(Rucksack {
+            first_half,
+            second_half,
+            shared,
+        })
+    }
+}
+
+impl Rucksack {
+    fn all_chars(&self) -> HashSet<char> {
+        &self.first_half | &self.second_half
+    }
+}
+
+const CHUNK_SIZE: usize = 3;
+
+#[derive(Debug)]
+pub struct Rucksacks {
+    rucksacks: Vec<Rucksack>,
+    // The item that every rucksack in each group of [CHUNK_SIZE] has.
+    badges: Vec<char>,
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Rucksacks;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let lines = numbered_lines(s);
+        let rucksacks = lines
+            .iter()
+            .map(|(line_number, line)| {
+                line.parse()
+                    .map_err(|e: ParseError| e.at_line(*line_number))
+            })
+            .collect::<Result<Vec<Rucksack>, _>>()?;
+
+        // Both parts need to make sense of the whole input, so check the groups here too.
+        let mut badges = vec![];
+        for (group, lines) in rucksacks.chunks(CHUNK_SIZE).zip(lines.chunks(CHUNK_SIZE)) {
+            if group.len() != CHUNK_SIZE {
+                let (last_line, _) = lines[lines.len() - 1];
+                return Err(ParseError::new(
+                    "",
+                    format!("{} rucksacks in the last group", CHUNK_SIZE),
+                )
+                .at_line(last_line + 1));
+            }
+            let in_every_rucksack = group
+                .iter()
+                .map(|r| r.all_chars())
+                .reduce(|r1, r2| &r1 & &r2)
+                .expect("BUG: reduce shouldn't have received chunk of size 0");
+            let badge = unique_item(in_every_rucksack, "exactly one item in the whole group")
+                .map_err(|e| e.at_line(lines[0].0))?;
+            badges.push(badge);
+        }
+        Ok(Rucksacks { rucksacks, badges })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let total_priority_of_unique_intersections: usize = input
+            .rucksacks
+            .iter()
+            .map(|r| char_priority(r.shared))
+            .sum();
+        total_priority_of_unique_intersections.into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let total_priority_of_elf_groups: usize =
+            input.badges.iter().copied().map(char_priority).sum();
+        total_priority_of_elf_groups.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day3::parse_example().unwrap();
+        assert_eq!(Day3::part1(&input), Answer::Int(157));
+        assert_eq!(Day3::part2(&input), Answer::Int(70));
+    }
+
+    #[test]
+    fn priorities() {
+        assert_eq!(char_priority('a'), 1);
+        assert_eq!(char_priority('z'), 26);
+        assert_eq!(char_priority('A'), 27);
+        assert_eq!(char_priority('Z'), 52);
+    }
+
+    #[test]
+    fn item_in_both_halves() {
+        let rucksack: Rucksack = "vJrwpWtwJgWrhcsFMMfFFhFp".parse().unwrap();
+        assert_eq!(rucksack.shared, 'p');
+    }
+
+    #[test]
+    fn odd_length_rucksack() {
+        assert!("abc".parse::<Rucksack>().is_err());
+        let error = "ab1d".parse::<Rucksack>().unwrap_err();
+        assert_eq!(error.column, Some(3));
+    }
+
+    #[test]
+    fn bad_groups() {
+        let error = |s| Day3::parse(s).unwrap_err().to_string();
+        assert_eq!(
+            error("abca\nabcd\n"),
+            "line 2: expected exactly one item in both compartments, found nothing"
+        );
+        assert_eq!(
+            error("abab\n"),
+            "line 1: expected exactly one item in both compartments, found \"ab\""
+        );
+        assert_eq!(
+            error("abca\nadae\nafag\nxyzx\n"),
+            "line 5: expected 3 rucksacks in the last group, found nothing"
+        );
+        assert_eq!(
+            error("abca\nabdb\nabeb\n"),
+            "line 1: expected exactly one item in the whole group, found \"ab\""
+        );
+    }
+}
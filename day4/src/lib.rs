@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use aoc_common::input::parse_lines;
+use aoc_common::intervals;
+use aoc_common::{Answer, ParseError, Solution};
+
+type Interval = intervals::Interval<usize>;
+
+#[derive(Debug)]
+pub struct TestCase(Interval, Interval);
+
+impl FromStr for TestCase {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, second) = s
+            .split_once(',')
+            .ok_or_else(|| ParseError::at_column(1, s, "two intervals separated by a comma"))?;
+
+        let first = first.parse()?;
+        let second = second
+            .parse()
+            .map_err(|e: ParseError| e.shift_columns(s.len() - second.len()))?;
+        Ok(TestCase(first, second))
+    }
+}
+
+impl TestCase {
+    fn one_contains_other(&self) -> bool {
+        self.0.contains(&self.1) || self.1.contains(&self.0)
+    }
+
+    fn one_overlaps_other(&self) -> bool {
+        self.0.overlaps(&self.1)
+    }
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Vec<TestCase>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse_lines(s)
+    }
+
+    fn part1(cases: &Self::Input) -> Answer {
+        let answer: usize = cases
+            .iter()
+            .filter(|t| t.one_contains_other())
+            .fold(0, |acc, _| acc + 1);
+
+        answer.into()
+    }
+
+    fn part2(cases: &Self::Input) -> Answer {
+        let answer: usize = cases
+            .iter()
+            .filter(|t| t.one_overlaps_other())
+            .fold(0, |acc, _| acc + 1);
+
+        answer.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day4::parse_example().unwrap();
+        assert_eq!(Day4::part1(&input), Answer::Int(2));
+        assert_eq!(Day4::part2(&input), Answer::Int(4));
+    }
+
+    #[test]
+    fn overlaps() {
+        // Sharing a single section counts.
+        assert!(Interval::new(5, 7).overlaps(&Interval::new(7, 9)));
+        assert!(Interval::new(7, 9).overlaps(&Interval::new(5, 7)));
+        assert!(Interval::new(2, 8).overlaps(&Interval::new(3, 7)));
+        assert!(Interval::new(6, 6).overlaps(&Interval::new(4, 6)));
+        assert!(!Interval::new(2, 4).overlaps(&Interval::new(6, 8)));
+        assert!(!Interval::new(6, 8).overlaps(&Interval::new(2, 4)));
+        assert!(!Interval::new(2, 3).overlaps(&Interval::new(4, 5)));
+    }
+
+    #[test]
+    fn contains() {
+        assert!(Interval::new(2, 8).contains(&Interval::new(3, 7)));
+        assert!(Interval::new(4, 6).contains(&Interval::new(6, 6)));
+        assert!(Interval::new(4, 6).contains(&Interval::new(4, 6)));
+        assert!(!Interval::new(3, 7).contains(&Interval::new(2, 8)));
+        assert!(!Interval::new(5, 7).contains(&Interval::new(7, 9)));
+    }
+
+    #[test]
+    fn backwards_interval() {
+        let error = "2-4,8-6".parse::<TestCase>().unwrap_err();
+        assert_eq!(error.column, Some(5));
+        assert_eq!(error.text, "8-6");
+    }
+
+    #[test]
+    fn bad_second_interval() {
+        let error = "2-4,6-x".parse::<TestCase>().unwrap_err();
+        assert_eq!(error.column, Some(7));
+        assert_eq!(error.text, "x");
+    }
+}
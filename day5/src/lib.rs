@@ -0,0 +1,801 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use aoc_common::input::{numbered_chunks, parse_number};
+use aoc_common::{Answer, ParseError, Solution};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Configuration(Vec<Vec<char>>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    count: usize,
+    // 1-indexed
+    from_index: usize,
+    to_index: usize,
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count, self.from_index, self.to_index
+        )
+    }
+}
+
+// Reads "move N from A to B", with the column in any error relative to the start of the line.
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = ["move", "<count>", "from", "<from>", "to", "<to>"];
+        let mut numbers = vec![];
+        let mut column = 1;
+        let mut words = s.split(' ');
+        for want in expected {
+            let is_number = want.starts_with('<');
+            let word = words.next().unwrap_or("");
+            if word.is_empty() {
+                let want = if is_number {
+                    "a number".to_string()
+                } else {
+                    format!("\"{}\"", want)
+                };
+                return Err(ParseError::at_column(column, word, want));
+            }
+            if is_number {
+                numbers.push(parse_number(word, column)?);
+            } else if word != want {
+                return Err(ParseError::at_column(column, word, format!("\"{}\"", want)));
+            }
+            column += word.len() + 1;
+        }
+        if let Some(extra) = words.next() {
+            return Err(ParseError::at_column(column, extra, "the end of the line"));
+        }
+
+        Ok(Instruction {
+            count: numbers[0],
+            from_index: numbers[1],
+            to_index: numbers[2],
+        })
+    }
+}
+
+// Why an instruction can't be carried out. Columns are 1-indexed, like in the instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadMove {
+    NoSuchColumn { column: usize, columns: usize },
+    SameColumn(usize),
+    NotEnoughCrates { column: usize, crates: usize },
+}
+
+impl fmt::Display for BadMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BadMove::NoSuchColumn { column, columns } => {
+                write!(f, "there's no column {} (there are {})", column, columns)
+            }
+            BadMove::SameColumn(column) => {
+                write!(f, "moving from column {} to itself", column)
+            }
+            BadMove::NotEnoughCrates { column, crates } => {
+                write!(f, "column {} only has {} crates", column, crates)
+            }
+        }
+    }
+}
+
+// A [BadMove] in a list of instructions. [number] is 1-indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoveError {
+    pub number: usize,
+    pub instruction: Instruction,
+    pub reason: BadMove,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} ({}): {}",
+            self.number, self.instruction, self.reason
+        )
+    }
+}
+
+impl Error for MoveError {}
+
+impl Configuration {
+    // The (0-indexed) columns to move between, if the instruction makes sense.
+    fn check_move(&self, instruction: &Instruction) -> Result<(usize, usize), BadMove> {
+        let columns = self.0.len();
+        let index = |column: usize| {
+            if (1..=columns).contains(&column) {
+                Ok(column - 1)
+            } else {
+                Err(BadMove::NoSuchColumn { column, columns })
+            }
+        };
+        let from = index(instruction.from_index)?;
+        let to = index(instruction.to_index)?;
+
+        if from == to {
+            return Err(BadMove::SameColumn(instruction.from_index));
+        }
+        if self.0[from].len() < instruction.count {
+            return Err(BadMove::NotEnoughCrates {
+                column: instruction.from_index,
+                crates: self.0[from].len(),
+            });
+        }
+        Ok((from, to))
+    }
+
+    // Moving the crates at most [capacity] at a time, where each lift keeps the crates it picks
+    // up in the same order. Returns the number of lifts. Nothing changes if the instruction is bad.
+    fn move_in_lifts(
+        &mut self,
+        instruction: &Instruction,
+        capacity: usize,
+    ) -> Result<usize, BadMove> {
+        assert!(capacity > 0, "BUG: crane can't lift anything");
+        let (from, to) = self.check_move(instruction)?;
+
+        let mut remaining = instruction.count;
+        let mut lifts = 0;
+        while remaining > 0 {
+            let lifted = remaining.min(capacity);
+            let split = self.0[from].len() - lifted;
+            let crates = self.0[from].split_off(split);
+            self.0[to].extend(crates);
+            remaining -= lifted;
+            lifts += 1;
+        }
+        Ok(lifts)
+    }
+
+    // Empty stacks don't have anything on top, so they're just skipped.
+    pub fn top_crates(&self) -> String {
+        self.0.iter().filter_map(|s| s.last()).collect()
+    }
+}
+
+// Drawn the same way as in the puzzle input, so [parse_configuration] can read it back in.
+impl fmt::Display for Configuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.0.iter().map(Vec::len).max().unwrap_or(0);
+        for row in (0..height).rev() {
+            let cells: Vec<String> = self
+                .0
+                .iter()
+                .map(|stack| match stack.get(row) {
+                    Some(c) => format!("[{}]", c),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            writeln!(f, "{}", cells.join(" "))?;
+        }
+        let numbers: Vec<String> = (1..=self.0.len()).map(|i| format!("{:^3}", i)).collect();
+        write!(f, "{}", numbers.join(" "))
+    }
+}
+
+// Something that can carry out instructions. There's a few different models.
+pub trait Crane {
+    // Carry out [instruction], returning how many separate lifts it took. Nothing changes if the
+    // instruction is bad.
+    fn apply(
+        &mut self,
+        configuration: &mut Configuration,
+        instruction: &Instruction,
+    ) -> Result<usize, BadMove>;
+}
+
+impl<C: Crane + ?Sized> Crane for &mut C {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        (**self).apply(c, i)
+    }
+}
+
+impl<C: Crane + ?Sized> Crane for Box<C> {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        (**self).apply(c, i)
+    }
+}
+
+// Part 1: moves crates one at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        c.move_in_lifts(i, 1)
+    }
+}
+
+// Part 2: moves any number of crates at once, so they stay in the same order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        c.move_in_lifts(i, usize::MAX)
+    }
+}
+
+// Somewhere in between: moves up to [capacity] crates at once, so big moves get split up.
+pub struct CappedCrane {
+    capacity: usize,
+}
+
+impl CappedCrane {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "BUG: crane can't lift anything");
+        CappedCrane { capacity }
+    }
+}
+
+impl Crane for CappedCrane {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        c.move_in_lifts(i, self.capacity)
+    }
+}
+
+// Wraps another crane, keeping a running total of how many lifts it's done.
+pub struct LiftCounter<C> {
+    crane: C,
+    pub lifts: usize,
+}
+
+impl<C: Crane> LiftCounter<C> {
+    pub fn new(crane: C) -> Self {
+        LiftCounter { crane, lifts: 0 }
+    }
+}
+
+impl<C: Crane> Crane for LiftCounter<C> {
+    fn apply(&mut self, c: &mut Configuration, i: &Instruction) -> Result<usize, BadMove> {
+        let lifts = self.crane.apply(c, i)?;
+        self.lifts += lifts;
+        Ok(lifts)
+    }
+}
+
+// The cranes you can pick from the command line: "9000", "9001" or "capped:<capacity>".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraneModel {
+    Mover9000,
+    Mover9001,
+    Capped(usize),
+}
+
+impl CraneModel {
+    pub fn crane(&self) -> Box<dyn Crane> {
+        match self {
+            CraneModel::Mover9000 => Box::new(CrateMover9000),
+            CraneModel::Mover9001 => Box::new(CrateMover9001),
+            CraneModel::Capped(capacity) => Box::new(CappedCrane::new(*capacity)),
+        }
+    }
+}
+
+impl FromStr for CraneModel {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expected = "a crane model (9000, 9001 or capped:<capacity>)";
+        match s {
+            "9000" => Ok(CraneModel::Mover9000),
+            "9001" => Ok(CraneModel::Mover9001),
+            _ => match s.strip_prefix("capped:").map(str::parse) {
+                Some(Ok(capacity)) if capacity > 0 => Ok(CraneModel::Capped(capacity)),
+                _ => Err(ParseError::at_column(1, s, expected)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for CraneModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CraneModel::Mover9000 => write!(f, "CrateMover 9000"),
+            CraneModel::Mover9001 => write!(f, "CrateMover 9001"),
+            CraneModel::Capped(capacity) => write!(f, "Crane with capacity {}", capacity),
+        }
+    }
+}
+
+// Each stack takes up four characters of the drawing, e.g. "[X] ", including the gap after it.
+const STACK_WIDTH: usize = 4;
+
+// Split a line of the drawing into the bit for each stack, along with the (1-indexed) column
+// that bit starts at.
+fn stack_cells(line: &str) -> Vec<(usize, String)> {
+    let chars: Vec<char> = line.chars().collect();
+    chars
+        .chunks(STACK_WIDTH)
+        .enumerate()
+        .map(|(i, cell)| (i * STACK_WIDTH + 1, cell.iter().collect()))
+        .collect()
+}
+
+// The line of stack numbers under the drawing, " 1   2   3 ". Returns how many stacks there are.
+fn parse_footer(line: &str) -> Result<usize, ParseError> {
+    let cells = stack_cells(line.trim_end());
+    if cells.is_empty() {
+        return Err(ParseError::new(line, "a line of stack numbers"));
+    }
+    for (i, (column, cell)) in cells.iter().enumerate() {
+        let expected = (i + 1).to_string();
+        if cell.trim() != expected {
+            return Err(ParseError::at_column(
+                *column,
+                cell.trim_end(),
+                format!("stack number {}", expected),
+            ));
+        }
+    }
+    Ok(cells.len())
+}
+
+// One stack's worth of a line of the drawing: either a crate like "[X]", or a gap.
+fn parse_crate(cell: &str) -> Result<Option<char>, ParseError> {
+    let chars: Vec<char> = cell.chars().collect();
+    let (body, gap) = chars.split_at(chars.len().min(STACK_WIDTH - 1));
+    if gap.iter().any(|c| *c != ' ') {
+        return Err(ParseError::at_column(
+            STACK_WIDTH,
+            gap.iter().collect::<String>(),
+            "a space between stacks",
+        ));
+    }
+    match body {
+        [] | [' '] | [' ', ' '] | [' ', ' ', ' '] => Ok(None),
+        ['[', c, ']'] if c.is_ascii_uppercase() => Ok(Some(*c)),
+        _ => Err(ParseError::at_column(
+            1,
+            body.iter().collect::<String>(),
+            "a crate like [X], or a gap",
+        )),
+    }
+}
+
+// The drawing of the stacks, including the footer. The lines don't need to be padded out to the
+// full width with trailing spaces.
+fn parse_configuration(lines: &[(usize, &str)]) -> Result<Configuration, ParseError> {
+    let ((footer_line, footer), rows) = lines
+        .split_last()
+        .ok_or_else(|| ParseError::new("", "a drawing of the stacks"))?;
+    let stack_count = parse_footer(footer).map_err(|e| e.at_line(*footer_line))?;
+
+    // Go from the bottom up, so the crates get pushed on in the right order.
+    let mut stacks = vec![vec![]; stack_count];
+    for (height, (line_number, row)) in rows.iter().rev().enumerate() {
+        for (i, (column, cell)) in stack_cells(row).into_iter().enumerate() {
+            let error = |e: ParseError| e.at_line(*line_number);
+            let crate_ = parse_crate(&cell).map_err(|e| error(e.shift_columns(column - 1)))?;
+            let Some(crate_) = crate_ else { continue };
+
+            if i >= stack_count {
+                return Err(error(ParseError::at_column(
+                    column,
+                    cell.trim_end(),
+                    format!("at most {} stacks, like the footer", stack_count),
+                )));
+            }
+            if stacks[i].len() != height {
+                return Err(error(ParseError::at_column(
+                    column,
+                    cell.trim_end(),
+                    "a crate sitting on top of another crate",
+                )));
+            }
+            stacks[i].push(crate_);
+        }
+    }
+    Ok(Configuration(stacks))
+}
+
+fn parse_instructions(lines: &[(usize, &str)]) -> Result<Vec<Instruction>, ParseError> {
+    lines
+        .iter()
+        .map(|(line_number, s)| s.parse().map_err(|e: ParseError| e.at_line(*line_number)))
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Procedure {
+    starting_configuration: Configuration,
+    instructions: Vec<Instruction>,
+}
+
+impl Procedure {
+    // The number of instructions.
+    pub fn len(&self) -> usize {
+        self.instructions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instructions.is_empty()
+    }
+
+    pub fn try_run(&self, crane: impl Crane) -> Result<Configuration, MoveError> {
+        let mut history = self.history(crane);
+        history.run_to_end()?;
+        Ok(history.current().clone())
+    }
+
+    fn run(&self, crane: impl Crane) -> Configuration {
+        self.try_run(crane).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn history<C: Crane>(&self, crane: C) -> History<'_, C> {
+        History {
+            instructions: &self.instructions,
+            crane,
+            configurations: vec![self.starting_configuration.clone()],
+            position: 0,
+        }
+    }
+}
+
+// Running through the instructions one at a time, with the option of going back. Handy for
+// working out where a list of instructions went wrong.
+pub struct History<'a, C> {
+    instructions: &'a [Instruction],
+    crane: C,
+    // The configuration after each instruction we've run so far, starting with the starting
+    // configuration. We keep them around after stepping back, so stepping forwards again is free.
+    configurations: Vec<Configuration>,
+    // How many instructions have been applied to get to the current configuration.
+    position: usize,
+}
+
+impl<C: Crane> History<'_, C> {
+    pub fn current(&self) -> &Configuration {
+        &self.configurations[self.position]
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.instructions.len()
+    }
+
+    // The instruction that got us to the current configuration.
+    pub fn last_instruction(&self) -> Option<&Instruction> {
+        self.instructions.get(self.position.checked_sub(1)?)
+    }
+
+    // The instruction that would be run by the next step forward.
+    pub fn next_instruction(&self) -> Option<&Instruction> {
+        self.instructions.get(self.position)
+    }
+
+    // Returns false if we're already at the end. If the next instruction is bad, we stay where we
+    // are.
+    pub fn step_forward(&mut self) -> Result<bool, MoveError> {
+        let Some(instruction) = self.next_instruction().copied() else {
+            return Ok(false);
+        };
+        if self.configurations.len() == self.position + 1 {
+            let mut next = self.current().clone();
+            self.crane
+                .apply(&mut next, &instruction)
+                .map_err(|reason| MoveError {
+                    number: self.position + 1,
+                    instruction,
+                    reason,
+                })?;
+            self.configurations.push(next);
+        }
+        self.position += 1;
+        Ok(true)
+    }
+
+    // Returns false if we're already at the start.
+    pub fn step_back(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    pub fn run_to_end(&mut self) -> Result<(), MoveError> {
+        while self.step_forward()? {}
+        Ok(())
+    }
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Procedure;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        match &numbered_chunks(s)[..] {
+            [drawing, instructions] => Ok(Procedure {
+                starting_configuration: parse_configuration(drawing)?,
+                instructions: parse_instructions(instructions)?,
+            }),
+            _ => Err(ParseError::new(
+                "",
+                "a drawing of the stacks, then a blank line, then the moves",
+            )),
+        }
+    }
+
+    fn part1(procedure: &Procedure) -> Answer {
+        procedure.run(CrateMover9000).top_crates().into()
+    }
+
+    fn part2(procedure: &Procedure) -> Answer {
+        procedure.run(CrateMover9001).top_crates().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_configuration() -> Configuration {
+        Day5::parse_example().unwrap().starting_configuration
+    }
+
+    fn parse_drawing(drawing: &str) -> Result<Configuration, ParseError> {
+        parse_configuration(&numbered_chunks(drawing)[0])
+    }
+
+    #[test]
+    fn example() {
+        let input = Day5::parse_example().unwrap();
+        assert_eq!(Day5::part1(&input), Answer::Text("CMZ".to_string()));
+        assert_eq!(Day5::part2(&input), Answer::Text("MCD".to_string()));
+    }
+
+    #[test]
+    fn crane_models_differ_on_multi_crate_moves() {
+        let instruction = Instruction {
+            count: 2,
+            from_index: 2,
+            to_index: 1,
+        };
+
+        let mut one_at_a_time = example_configuration();
+        CrateMover9000
+            .apply(&mut one_at_a_time, &instruction)
+            .unwrap();
+        assert_eq!(one_at_a_time.0[0], vec!['Z', 'N', 'D', 'C']);
+
+        let mut all_at_once = example_configuration();
+        CrateMover9001
+            .apply(&mut all_at_once, &instruction)
+            .unwrap();
+        assert_eq!(all_at_once.0[0], vec!['Z', 'N', 'C', 'D']);
+    }
+
+    #[test]
+    fn capped_crane_splits_big_moves() {
+        let instruction = Instruction {
+            count: 3,
+            from_index: 1,
+            to_index: 2,
+        };
+        let mut configuration = Configuration(vec![vec!['A', 'B', 'C'], vec![]]);
+        assert_eq!(
+            CappedCrane::new(2).apply(&mut configuration, &instruction),
+            Ok(2)
+        );
+        // 'B' and 'C' get lifted first, then 'A' goes on top.
+        assert_eq!(configuration.0[1], vec!['B', 'C', 'A']);
+    }
+
+    #[test]
+    fn count_lifts() {
+        let procedure = Day5::parse_example().unwrap();
+        let lifts = |model: &str| {
+            let model: CraneModel = model.parse().unwrap();
+            let mut crane = LiftCounter::new(model.crane());
+            let top_crates = procedure.try_run(&mut crane).unwrap().top_crates();
+            (top_crates, crane.lifts)
+        };
+        assert_eq!(lifts("9000"), ("CMZ".to_string(), 7));
+        assert_eq!(lifts("9001"), ("MCD".to_string(), 4));
+        assert_eq!(lifts("capped:1"), ("CMZ".to_string(), 7));
+        assert_eq!(lifts("capped:2"), ("MCZ".to_string(), 5));
+        assert_eq!(lifts("capped:3"), ("MCD".to_string(), 4));
+    }
+
+    #[test]
+    fn parse_crane_model() {
+        assert_eq!("capped:12".parse(), Ok(CraneModel::Capped(12)));
+        assert!("capped:0".parse::<CraneModel>().is_err());
+        assert!("capped:".parse::<CraneModel>().is_err());
+        assert!("9002".parse::<CraneModel>().is_err());
+    }
+
+    #[test]
+    fn bad_moves() {
+        let mut configuration = example_configuration();
+        let mut check = |count, from_index, to_index| {
+            let instruction = Instruction {
+                count,
+                from_index,
+                to_index,
+            };
+            CrateMover9000
+                .apply(&mut configuration, &instruction)
+                .unwrap_err()
+        };
+        assert_eq!(
+            check(1, 4, 1),
+            BadMove::NoSuchColumn {
+                column: 4,
+                columns: 3
+            }
+        );
+        assert_eq!(
+            check(1, 1, 0),
+            BadMove::NoSuchColumn {
+                column: 0,
+                columns: 3
+            }
+        );
+        assert_eq!(check(1, 2, 2), BadMove::SameColumn(2));
+        assert_eq!(
+            check(2, 3, 1),
+            BadMove::NotEnoughCrates {
+                column: 3,
+                crates: 1
+            }
+        );
+
+        // Bad moves don't change anything.
+        assert_eq!(configuration, example_configuration());
+    }
+
+    #[test]
+    fn errors_say_which_instruction_went_wrong() {
+        let mut procedure = Day5::parse_example().unwrap();
+        procedure.instructions[2].count = 5;
+        let error = procedure.try_run(CrateMover9001).unwrap_err();
+        assert_eq!(error.number, 3);
+        assert_eq!(
+            error.to_string(),
+            "instruction 3 (move 5 from 2 to 1): column 2 only has 2 crates"
+        );
+    }
+
+    #[test]
+    fn step_through_history() {
+        let procedure = Day5::parse_example().unwrap();
+        let mut history = procedure.history(CrateMover9000);
+        assert!(!history.step_back());
+
+        assert_eq!(history.step_forward(), Ok(true));
+        assert_eq!(history.step_forward(), Ok(true));
+        let after_two = history.current().clone();
+        assert_eq!(
+            after_two.0,
+            vec![vec![], vec!['M', 'C'], vec!['P', 'D', 'N', 'Z']]
+        );
+
+        assert!(history.step_back());
+        assert!(history.step_back());
+        assert_eq!(history.position(), 0);
+        assert_eq!(history.current(), &example_configuration());
+
+        assert_eq!(history.step_forward(), Ok(true));
+        assert_eq!(history.step_forward(), Ok(true));
+        assert_eq!(history.current(), &after_two);
+        assert_eq!(
+            history.next_instruction().map(|i| i.to_string()),
+            Some("move 2 from 2 to 1".to_string())
+        );
+
+        history.run_to_end().unwrap();
+        assert!(history.is_finished());
+        assert_eq!(history.current().top_crates(), "CMZ");
+        assert_eq!(history.step_forward(), Ok(false));
+    }
+
+    #[test]
+    fn parse_drawing_of_the_stacks() {
+        assert_eq!(
+            example_configuration().0,
+            vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']]
+        );
+
+        // Trailing spaces are optional, and stacks can start off empty.
+        let configuration = parse_drawing("[A]\n[B]         [C]\n 1   2   3   4\n").unwrap();
+        assert_eq!(
+            configuration.0,
+            vec![vec!['B', 'A'], vec![], vec![], vec!['C']]
+        );
+    }
+
+    #[test]
+    fn render() {
+        let drawing: Vec<&str> = Day5::EXAMPLE.lines().take(4).collect();
+        assert_eq!(example_configuration().to_string(), drawing.join("\n"));
+
+        // Whatever state the stacks get into, we can read them back in.
+        let procedure = Day5::parse_example().unwrap();
+        let mut history = procedure.history(CrateMover9000);
+        loop {
+            let rendered = history.current().to_string();
+            assert_eq!(parse_drawing(&rendered).as_ref(), Ok(history.current()));
+            if !history.step_forward().unwrap() {
+                break;
+            }
+        }
+        assert_eq!(
+            history.current().to_string(),
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 "
+        );
+
+        let configuration = Configuration(vec![vec!['A'], vec![]]);
+        assert_eq!(configuration.to_string(), "[A]    \n 1   2 ");
+        assert_eq!(parse_drawing(&configuration.to_string()), Ok(configuration));
+    }
+
+    #[test]
+    fn drawing_errors() {
+        // The footer has to match the stacks in the drawing.
+        let error = parse_drawing("[A] [B] [C]\n 1   2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(9)));
+        let error = parse_drawing("[A] [B]\n 1   3\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(5)));
+
+        // Crates can't float in mid-air.
+        let error = parse_drawing("    [A]\n[B]\n 1   2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(5)));
+
+        let error = parse_drawing("[A] (B)\n 1   2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(5)));
+        let error = parse_drawing("[A]-[B]\n 1   2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(4)));
+
+        assert!(Day5::parse("move 1 from 2 to 1\n").is_err());
+    }
+
+    #[test]
+    fn bad_instructions() {
+        let error = |instruction: &str| {
+            Day5::parse(&format!("[A]\n 1   2\n\n{}\n", instruction))
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("move x from 1 to 2"),
+            "line 4, column 6: expected a number, found \"x\""
+        );
+        assert_eq!(
+            error("move 1 form 1 to 2"),
+            "line 4, column 8: expected \"from\", found \"form\""
+        );
+        assert_eq!(
+            error("move 1 from 1"),
+            "line 4, column 15: expected \"to\", found nothing"
+        );
+        assert_eq!(
+            error("move 1 from 1 to 2 please"),
+            "line 4, column 20: expected the end of the line, found \"please\""
+        );
+    }
+
+    #[test]
+    fn empty_stacks() {
+        let procedure = Day5::parse("[A]\n 1   2\n\nmove 1 from 1 to 2\n").unwrap();
+        assert_eq!(procedure.starting_configuration.top_crates(), "A");
+        assert_eq!(Day5::part1(&procedure), Answer::Text("A".to_string()));
+    }
+}
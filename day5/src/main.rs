@@ -1,88 +1,115 @@
-use std::fs::read_to_string;
+use std::process;
+use std::thread;
+use std::time::Duration;
 
-#[derive(Debug)]
-struct Configuration(Vec<Vec<char>>);
-struct Instruction {
-    count: usize,
-    // 1-indexed
-    from_index: usize,
-    to_index: usize,
+use aoc_common::cli::{usage_error, Args, InputSource};
+use aoc_common::solution::{load, print_answers};
+use day5::{Configuration, Crane, CraneModel, Day5, LiftCounter, MoveError, Procedure};
+
+const USAGE: &str = "\
+Usage: day5 [<input> | - | --example] [--crane <model>,...] [--animate [--delay <ms>]]
+
+Without --crane or --animate, prints both parts as usual. Otherwise, runs the instructions with
+each crane and prints the crates that end up on top. <model> is 9000, 9001, or capped:<n> for a
+crane that can lift at most n crates at once.
+
+--animate redraws the stacks after every instruction, waiting --delay milliseconds (default
+200) in between. It uses the CrateMover 9000 unless you pick a crane.";
+
+struct Options {
+    source: InputSource,
+    models: Vec<CraneModel>,
+    // How long to wait between frames, if we're animating.
+    animate: Option<Duration>,
 }
 
-impl Configuration {
-    fn apply_move(&mut self, instruction: &Instruction) {
-        let from = instruction.from_index - 1;
-        let to = instruction.to_index - 1;
-        for _ in 0..instruction.count {
-            let v = self.0[from].pop().expect("BUG: Move makes column empty");
-            self.0[to].push(v)
-        }
-    }
+fn parse_args() -> Result<Options, String> {
+    let mut args = Args::from_env();
+    let models = match args.value("--crane")? {
+        None => vec![],
+        Some(models) => models
+            .split(',')
+            .map(|model| model.parse().map_err(|e| format!("--crane: {}", e)))
+            .collect::<Result<_, _>>()?,
+    };
+    let delay = args.parsed_value("--delay")?;
+    let animate = match (args.flag("--animate"), delay) {
+        (true, delay) => Some(Duration::from_millis(delay.unwrap_or(200))),
+        (false, None) => None,
+        (false, Some(_)) => return Err("--delay only makes sense with --animate".to_string()),
+    };
+    let source = args.input_source(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))?;
+    args.finish()?;
+    Ok(Options {
+        source,
+        models,
+        animate,
+    })
+}
 
-    fn apply_move2(&mut self, instruction: &Instruction) {
-        let from = instruction.from_index - 1;
-        let to = instruction.to_index - 1;
-        let mut crates_to_move: Vec<char> = vec![];
-        for _ in 0..instruction.count {
-            let v = self.0[from].pop().expect("BUG: Move makes column empty");
-            crates_to_move.push(v);
-        }
-        // Reverse it. This means that we'll push on in reverse order.
-        crates_to_move.reverse();
-        for create in crates_to_move {
-            self.0[to].push(create)
-        }
+fn draw(step: usize, steps: usize, instruction: String, configuration: &Configuration) {
+    // Clear the screen and go back to the top left.
+    print!("\x1b[2J\x1b[H");
+    println!("Step {}/{}: {}\n", step, steps, instruction);
+    println!("{}\n", configuration);
+}
+
+fn animate(
+    procedure: &Procedure,
+    crane: impl Crane,
+    delay: Duration,
+) -> Result<Configuration, MoveError> {
+    let steps = procedure.len();
+    let mut history = procedure.history(crane);
+    draw(0, steps, "start".to_string(), history.current());
+    while history.step_forward()? {
+        thread::sleep(delay);
+        let instruction = history
+            .last_instruction()
+            .expect("BUG: stepped forward without an instruction");
+        draw(
+            history.position(),
+            steps,
+            instruction.to_string(),
+            history.current(),
+        );
     }
+    Ok(history.current().clone())
 }
 
 fn main() {
-    // Laziness: I can't be bothered to parse out the configuration.
-    let starting_configuration = Configuration(vec![
-        vec!['F', 'D', 'B', 'Z', 'T', 'J', 'R', 'N'],
-        vec!['R', 'S', 'N', 'J', 'H'],
-        vec!['C', 'R', 'N', 'J', 'G', 'Z', 'F', 'Q'],
-        vec!['F', 'V', 'N', 'G', 'R', 'T', 'Q'],
-        vec!['L', 'T', 'Q', 'F'],
-        vec!['Q', 'C', 'W', 'Z', 'B', 'R', 'G', 'N'],
-        vec!['F', 'C', 'L', 'S', 'N', 'H', 'M'],
-        vec!['D', 'N', 'Q', 'M', 'T', 'J'],
-        vec!['P', 'G', 'S'],
-    ]);
+    let mut options = parse_args().unwrap_or_else(|e| usage_error(USAGE, &e));
+    let procedure = load::<Day5>(&options.source).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
 
-    let instructions: Vec<Instruction> = read_to_string("input")
-        .expect("Failed to read input file")
-        .split('\n')
-        .filter(|s| s.starts_with("move"))
-        .map(|s| {
-            let words: Vec<&str> = s.split(' ').collect();
-            let count: usize = words[1].parse().unwrap();
-            let from_index: usize = words[3].parse().unwrap();
-            let to_index: usize = words[5].parse().unwrap();
-            Instruction {
-                count,
-                from_index,
-                to_index,
+    if options.models.is_empty() {
+        match options.animate {
+            None => {
+                print_answers::<Day5>(&procedure);
+                return;
             }
-        })
-        .collect();
-
-    // Part 1
-    let mut configuration = Configuration(starting_configuration.0.to_vec());
-    for instruction in &instructions {
-        configuration.apply_move(instruction)
+            Some(_) => options.models.push(CraneModel::Mover9000),
+        }
     }
-    let output: String = configuration.0.iter().map(|s| s[s.len() - 1]).collect();
-
-    println!("{:?}", configuration);
-    println!("{:?}", output);
-
-    // Part 2
-    let mut configuration = Configuration(starting_configuration.0.to_vec());
-    for instruction in &instructions {
-        configuration.apply_move2(instruction)
+    for model in options.models {
+        let mut crane = LiftCounter::new(model.crane());
+        let result = match options.animate {
+            None => procedure.try_run(&mut crane),
+            Some(delay) => animate(&procedure, &mut crane, delay),
+        };
+        match result {
+            Ok(configuration) => println!(
+                "{}: {} ({} lifts)",
+                model,
+                configuration.top_crates(),
+                crane.lifts
+            ),
+            Err(e) => {
+                eprintln!("{}: {}", model, e);
+                process::exit(1)
+            }
+        }
     }
-    let output: String = configuration.0.iter().map(|s| s[s.len() - 1]).collect();
-
-    println!("{:?}", configuration);
-    println!("{:?}", output);
 }
@@ -0,0 +1,309 @@
+use std::io::{self, Read};
+
+use aoc_common::{Answer, ParseError, Solution};
+
+// The two kinds of marker in the puzzle, as window sizes.
+pub const START_OF_PACKET: usize = 4;
+pub const START_OF_MESSAGE: usize = 14;
+
+// Slides a window along a datastream a byte at a time, keeping a count of each byte in the
+// window and of how many bytes appear in it more than once. So each byte is O(1) to add,
+// however big the window is, and we never need more than the window in memory.
+pub struct MarkerDetector {
+    size: usize,
+    // The last [size] bytes, as a ring buffer. Once it's full, the oldest is at
+    // [position % size].
+    window: Vec<u8>,
+    // How much of the window is filled in. This is only less than [size] at the start, or
+    // after a [reset].
+    len: usize,
+    counts: [usize; 256],
+    duplicates: usize,
+    position: usize,
+}
+
+impl MarkerDetector {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0, "BUG: marker window can't be empty");
+        MarkerDetector {
+            size,
+            window: vec![0; size],
+            len: 0,
+            counts: [0; 256],
+            duplicates: 0,
+            position: 0,
+        }
+    }
+
+    // How many bytes we've seen so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // Forget the bytes in the window, so the next marker has to be made of bytes after this point.
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.counts = [0; 256];
+        self.duplicates = 0;
+    }
+
+    // Add the next byte, and say whether the last [size] bytes are all different.
+    pub fn push(&mut self, byte: u8) -> bool {
+        let slot = self.position % self.size;
+        if self.len == self.size {
+            let oldest = self.window[slot] as usize;
+            self.counts[oldest] -= 1;
+            if self.counts[oldest] == 1 {
+                self.duplicates -= 1;
+            }
+        } else {
+            self.len += 1;
+        }
+
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 2 {
+            self.duplicates += 1;
+        }
+        self.window[slot] = byte;
+        self.position += 1;
+
+        self.len == self.size && self.duplicates == 0
+    }
+}
+
+// Every position where a marker ends, in order. See [markers].
+pub struct Markers<I> {
+    bytes: I,
+    detector: MarkerDetector,
+    non_overlapping: bool,
+}
+
+impl<I> Markers<I> {
+    // Only count markers that start after the previous one ended.
+    pub fn non_overlapping(self) -> Self {
+        Markers {
+            non_overlapping: true,
+            ..self
+        }
+    }
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Markers<I> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        for byte in self.bytes.by_ref() {
+            if self.detector.push(byte) {
+                if self.non_overlapping {
+                    self.detector.reset();
+                }
+                return Some(self.detector.position());
+            }
+        }
+        None
+    }
+}
+
+// The positions of all the markers of [size], counted the same way as the puzzle (i.e. how many
+// bytes have been read when the marker's complete).
+pub fn markers<I: IntoIterator<Item = u8>>(bytes: I, size: usize) -> Markers<I::IntoIter> {
+    Markers {
+        bytes: bytes.into_iter(),
+        detector: MarkerDetector::new(size),
+        non_overlapping: false,
+    }
+}
+
+// Counts of how many markers fall in each of [buckets] equal slices of a datastream of [len]
+// bytes. Each bucket is given as the first and last positions it covers, and its count.
+pub fn histogram(
+    positions: impl IntoIterator<Item = usize>,
+    len: usize,
+    buckets: usize,
+) -> Vec<(usize, usize, usize)> {
+    assert!(buckets > 0, "BUG: histogram needs at least one bucket");
+    let width = len.div_ceil(buckets).max(1);
+    let mut counts = vec![0; len.div_ceil(width)];
+    for position in positions {
+        counts[(position - 1) / width] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (i * width + 1, ((i + 1) * width).min(len), count))
+        .collect()
+}
+
+// Line breaks aren't part of the signal, wherever they are, so [Day6::parse] drops them too.
+// Otherwise a datastream that's been wrapped onto several lines would give different positions
+// depending on how it was read.
+fn is_line_break(byte: u8) -> bool {
+    byte == b'\n' || byte == b'\r'
+}
+
+// How many bytes have to be read before the first marker of [size] is complete (which is what the
+// puzzle asks for), if there is one.
+pub fn find_marker(bytes: impl IntoIterator<Item = u8>, size: usize) -> Option<usize> {
+    markers(bytes, size).next()
+}
+
+// Like [find_marker], but for several sizes at once, reading the datastream a chunk at a time.
+// We stop reading once every marker's been found. Line breaks are skipped, like in [Day6::parse].
+pub fn find_markers_in_reader(
+    mut reader: impl Read,
+    sizes: &[usize],
+) -> io::Result<Vec<Option<usize>>> {
+    let mut detectors: Vec<MarkerDetector> = sizes
+        .iter()
+        .map(|size| MarkerDetector::new(*size))
+        .collect();
+    let mut found = vec![None; sizes.len()];
+    let mut buffer = [0; 1 << 16];
+
+    while found.contains(&None) {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        for byte in buffer[..read].iter().filter(|b| !is_line_break(**b)) {
+            for (detector, found) in detectors.iter_mut().zip(found.iter_mut()) {
+                if detector.push(*byte) && found.is_none() {
+                    *found = Some(detector.position());
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+fn first_marker_answer(s: &str, size: usize) -> Answer {
+    match find_marker(s.bytes(), size) {
+        Some(position) => position.into(),
+        None => "no marker".to_string().into(),
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = String;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        Ok(s.replace(['\n', '\r'], ""))
+    }
+
+    fn part1(s: &Self::Input) -> Answer {
+        first_marker_answer(s, START_OF_PACKET)
+    }
+
+    fn part2(s: &Self::Input) -> Answer {
+        first_marker_answer(s, START_OF_MESSAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OTHER_EXAMPLES: [(&str, usize, usize); 4] = [
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz\n", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg\n", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg\n", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw\n", 11, 26),
+    ];
+
+    #[test]
+    fn example() {
+        let input = Day6::parse_example().unwrap();
+        assert_eq!(Day6::part1(&input), Answer::Int(7));
+        assert_eq!(Day6::part2(&input), Answer::Int(19));
+    }
+
+    #[test]
+    fn other_examples_from_the_puzzle() {
+        for (s, packet, message) in OTHER_EXAMPLES {
+            assert_eq!(find_marker(s.bytes(), 4), Some(packet), "{}", s);
+            assert_eq!(find_marker(s.bytes(), 14), Some(message), "{}", s);
+        }
+    }
+
+    #[test]
+    fn read_a_stream() {
+        for (s, packet, message) in OTHER_EXAMPLES {
+            let sizes = [START_OF_PACKET, START_OF_MESSAGE];
+            let found = find_markers_in_reader(s.as_bytes(), &sizes).unwrap();
+            assert_eq!(found, vec![Some(packet), Some(message)], "{}", s);
+        }
+
+        // Line breaks don't count.
+        let found = find_markers_in_reader("ab\ncd\r\n".as_bytes(), &[4, 5]).unwrap();
+        assert_eq!(found, vec![Some(4), None]);
+    }
+
+    #[test]
+    fn line_breaks_are_the_same_either_way() {
+        let s = "mjqjp\nqmgbljsphd\r\nztnvjfqwrcgsmlb\n";
+        let input = Day6::parse(s).unwrap();
+        assert_eq!(input, Day6::EXAMPLE.trim_end());
+        let found =
+            find_markers_in_reader(s.as_bytes(), &[START_OF_PACKET, START_OF_MESSAGE]).unwrap();
+        assert_eq!(found, vec![Some(7), Some(19)]);
+        assert_eq!(Day6::part1(&input), Answer::Int(7));
+        assert_eq!(Day6::part2(&input), Answer::Int(19));
+    }
+
+    #[test]
+    fn detector() {
+        let mut detector = MarkerDetector::new(3);
+        let unique: Vec<bool> = "abcabbcda".bytes().map(|b| detector.push(b)).collect();
+        assert_eq!(
+            unique,
+            vec![false, false, true, true, true, false, false, true, true]
+        );
+        assert_eq!(detector.position(), 9);
+
+        assert_eq!(find_marker("aaaa".bytes(), 1), Some(1));
+        assert_eq!(find_marker("aaaa".bytes(), 2), None);
+        assert_eq!(find_marker("".bytes(), 2), None);
+        assert_eq!(
+            Day6::part2(&"abcd".to_string()),
+            Answer::Text("no marker".to_string())
+        );
+    }
+
+    #[test]
+    fn all_markers() {
+        let s = "abcabbcda";
+        assert_eq!(
+            markers(s.bytes(), 3).collect::<Vec<_>>(),
+            vec![3, 4, 5, 8, 9]
+        );
+        // The second marker has to start at the 4th byte, so the first one that fits is "bcd".
+        assert_eq!(
+            markers(s.bytes(), 3).non_overlapping().collect::<Vec<_>>(),
+            vec![3, 8]
+        );
+        assert_eq!(markers("aaaa".bytes(), 1).non_overlapping().count(), 4);
+        assert_eq!(markers("aaaa".bytes(), 2).next(), None);
+    }
+
+    #[test]
+    fn marker_histogram() {
+        assert_eq!(
+            histogram([3, 4, 5, 8, 9], 9, 3),
+            vec![(1, 3, 1), (4, 6, 2), (7, 9, 2)]
+        );
+        // The last bucket can be shorter than the rest.
+        assert_eq!(
+            histogram([1, 10], 10, 4),
+            vec![(1, 3, 1), (4, 6, 0), (7, 9, 0), (10, 10, 1)]
+        );
+        assert_eq!(histogram([1], 1, 5), vec![(1, 1, 1)]);
+        assert_eq!(histogram([], 0, 5), vec![]);
+    }
+}
@@ -1,33 +1,132 @@
-use std::collections::HashSet;
-use std::fs::read_to_string;
+use std::fs::File;
+use std::io::{self, Read};
+use std::process;
 
-fn all_unique(window: &str) -> bool {
-    let mut seen = HashSet::new();
+use aoc_common::cli::{usage_error, Args, InputSource};
+use aoc_common::solution::{load, print_answers, Solution};
+use day6::{find_markers_in_reader, histogram, markers, Day6, START_OF_MESSAGE, START_OF_PACKET};
 
-    for c in window.chars() {
-        if seen.contains(&c) {
-            return false;
+const USAGE: &str = "\
+Usage: day6 [<input> | - | --example] [--stream]
+       day6 [<input> | - | --example] --histogram <size>,... [--buckets <n>] [--non-overlapping]
+
+--stream reads the datastream a chunk at a time rather than all at once, so it can be as big
+as you like (e.g. piped in on stdin).
+
+--histogram finds every marker of each size, and shows how they're spread through the
+datastream in <n> buckets (default 10). With --non-overlapping, a marker can only start after
+the previous one has finished.";
+
+// The longest bar in each histogram.
+const BAR_WIDTH: usize = 50;
+
+struct HistogramOptions {
+    sizes: Vec<usize>,
+    buckets: usize,
+    non_overlapping: bool,
+}
+
+fn print_histograms(s: &str, options: &HistogramOptions) {
+    for (i, size) in options.sizes.iter().enumerate() {
+        let mut positions = markers(s.bytes(), *size);
+        if options.non_overlapping {
+            positions = positions.non_overlapping();
+        }
+        let positions: Vec<usize> = positions.collect();
+        let buckets = histogram(positions.iter().copied(), s.len(), options.buckets);
+
+        if i > 0 {
+            println!();
+        }
+        println!("Window {}: {} markers", size, positions.len());
+        let largest = buckets
+            .iter()
+            .map(|(_, _, count)| *count)
+            .max()
+            .unwrap_or(0);
+        let digits = s.len().to_string().len();
+        for (first, last, count) in buckets {
+            let bar = (count * BAR_WIDTH).div_ceil(largest.max(1));
+            println!(
+                "{:>digits$}-{:>digits$} | {:<BAR_WIDTH$} {}",
+                first,
+                last,
+                "#".repeat(bar),
+                count,
+            );
         }
-        seen.insert(c);
     }
-    true
 }
 
-fn find_first_index_with_unique_window(s: &str, window_size: usize) -> usize {
-    let index: usize = (window_size - 1..s.len() - 1)
-        .find(|&ending_index| {
-            let window = &s[ending_index - (window_size - 1)..=ending_index];
-            all_unique(window)
+fn parse_histogram_options(args: &mut Args) -> Result<Option<HistogramOptions>, String> {
+    let buckets = args.parsed_value("--buckets")?;
+    let non_overlapping = args.flag("--non-overlapping");
+    let Some(sizes) = args.value("--histogram")? else {
+        return match (buckets, non_overlapping) {
+            (None, false) => Ok(None),
+            _ => {
+                Err("--buckets and --non-overlapping only make sense with --histogram".to_string())
+            }
+        };
+    };
+
+    let sizes = sizes
+        .split(',')
+        .map(|size| match size.parse() {
+            Ok(size) if size > 0 => Ok(size),
+            _ => Err(format!("Couldn't understand window size {}", size)),
         })
-        .expect("BUG: No window had all unique chars");
+        .collect::<Result<_, _>>()?;
+    let buckets = buckets.unwrap_or(10);
+    if buckets == 0 {
+        return Err("--buckets has to be at least 1".to_string());
+    }
+    Ok(Some(HistogramOptions {
+        sizes,
+        buckets,
+        non_overlapping,
+    }))
+}
+
+fn stream(source: &InputSource) -> Result<(), String> {
+    let reader: Box<dyn Read> = match source {
+        InputSource::File(path) => Box::new(
+            File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?,
+        ),
+        InputSource::Stdin => Box::new(io::stdin().lock()),
+        InputSource::Example => Box::new(Day6::EXAMPLE.as_bytes()),
+    };
+    let found = find_markers_in_reader(reader, &[START_OF_PACKET, START_OF_MESSAGE])
+        .map_err(|e| format!("Failed to read datastream: {}", e))?;
 
-    // The problem wants 1-indexing, so add 1
-    index + 1
+    for (part, found) in found.into_iter().enumerate() {
+        match found {
+            Some(position) => println!("Part {}: {}", part + 1, position),
+            None => println!("Part {}: no marker", part + 1),
+        }
+    }
+    Ok(())
 }
 
 fn main() {
-    let s: String = read_to_string("input").expect("Failed to read input file");
+    let mut args = Args::from_env();
+    let streaming = args.flag("--stream");
+    let histogram = parse_histogram_options(&mut args).unwrap_or_else(|e| usage_error(USAGE, &e));
+    let source = args
+        .input_source(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))
+        .and_then(|source| args.finish().map(|()| source))
+        .unwrap_or_else(|e| usage_error(USAGE, &e));
 
-    println!("{}", find_first_index_with_unique_window(&s, 4));
-    println!("{}", find_first_index_with_unique_window(&s, 14));
+    let result = match (streaming, histogram) {
+        (true, Some(_)) => usage_error(USAGE, "Can't pass both --stream and --histogram"),
+        (true, None) => stream(&source),
+        (false, Some(options)) => {
+            load::<Day6>(&source).map(|parsed| print_histograms(&parsed, &options))
+        }
+        (false, None) => load::<Day6>(&source).map(|parsed| print_answers::<Day6>(&parsed)),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1)
+    }
 }
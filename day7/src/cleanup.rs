@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+
+use crate::filesystem::{Filesystem, NodeId};
+
+// Working out what to delete to get enough free space. Part 2 only ever deletes a single
+// directory, but deleting a few smaller things can free up enough while losing less.
+//
+// A plan is a set of files and directories where none is inside another. Finding the one with
+// the smallest total size is a knapsack problem over the tree. Line the nodes up in pre-order
+// (every directory is followed by everything inside it). Then working along the line, at each
+// node we either delete it and jump past everything inside it, or keep going. For each position
+// we keep a bitset of the totals we could have deleted by the time we get there.
+//
+// A total that's possible at one position is also possible at the next, since we could always
+// just not delete that node. So rather than keeping every bitset around to work out the plan at
+// the end, it's enough to remember the first position each total showed up at.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    // Lose as little as possible.
+    SmallestTotal,
+    // Run rm as few times as possible, and lose as little as possible doing that. Deleting "/"
+    // frees everything, so this is always a single deletion if there's a plan at all.
+    FewestDeletions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub deletions: Vec<NodeId>,
+    pub freed: usize,
+    // Free space before and after carrying out the plan.
+    pub free_before: usize,
+    pub free_after: usize,
+}
+
+struct Bitset {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl Bitset {
+    fn new(len: usize) -> Self {
+        Bitset {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    // self |= other << shift, dropping anything that goes off the end.
+    fn or_shifted(&mut self, other: &Bitset, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        for i in (words..self.words.len()).rev() {
+            let mut shifted = other.words[i - words] << bits;
+            if bits > 0 && i > words {
+                shifted |= other.words[i - words - 1] >> (64 - bits);
+            }
+            self.words[i] |= shifted;
+        }
+        // The last word can have bits past the end.
+        if !self.len.is_multiple_of(64) {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << (self.len % 64)) - 1;
+        }
+    }
+
+    // Everything in [other] that isn't in [self] yet, so it's then in both.
+    fn merge_new(&mut self, other: &Bitset) -> Vec<usize> {
+        let mut new = vec![];
+        for (i, (word, other_word)) in self.words.iter_mut().zip(&other.words).enumerate() {
+            let mut added = other_word & !*word;
+            *word |= added;
+            while added != 0 {
+                new.push(i * 64 + added.trailing_zeros() as usize);
+                added &= added - 1;
+            }
+        }
+        new
+    }
+}
+
+// [dirs_only] means only whole directories can be deleted, like in the puzzle.
+pub fn plan_cleanup(
+    filesystem: &Filesystem,
+    disk_size: usize,
+    required_free: usize,
+    objective: Objective,
+    dirs_only: bool,
+) -> Option<Plan> {
+    let used = filesystem.size(filesystem.root());
+    let free_before = disk_size.checked_sub(used)?;
+    let need = required_free.saturating_sub(free_before);
+    let plan = |deletions: Vec<NodeId>| {
+        let freed = deletions.iter().map(|id| filesystem.size(*id)).sum();
+        Plan {
+            deletions,
+            freed,
+            free_before,
+            free_after: free_before + freed,
+        }
+    };
+    if need == 0 {
+        return Some(plan(vec![]));
+    }
+
+    let order = filesystem.descendants(filesystem.root());
+    let deletable = |id: NodeId| !dirs_only || filesystem.is_dir(id);
+
+    // The best single deletion. This is the answer for [FewestDeletions], and nothing bigger can
+    // be the answer for [SmallestTotal], so it's as big as the bitsets need to go.
+    let best_single = order
+        .iter()
+        .copied()
+        .filter(|id| deletable(*id) && filesystem.size(*id) >= need)
+        .min_by_key(|id| filesystem.size(*id))?;
+    if objective == Objective::FewestDeletions {
+        return Some(plan(vec![best_single]));
+    }
+    let limit = filesystem.size(best_single);
+
+    // [end[i]] is the position just after everything inside [order[i]].
+    let position: HashMap<NodeId, usize> =
+        order.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let mut end: Vec<usize> = (1..=order.len()).collect();
+    for (i, id) in order.iter().enumerate().rev() {
+        if let Some(parent) = filesystem.parent_dir(*id) {
+            let parent = position[&parent];
+            end[parent] = end[parent].max(end[i]);
+        }
+    }
+
+    let mut first_seen = vec![usize::MAX; limit + 1];
+    let mut current = Bitset::new(limit + 1);
+    current.insert(0);
+    first_seen[0] = 0;
+    // Totals that we can get to by deleting something and jumping ahead, keyed by where we land.
+    // There's only ever one of these for each directory we're inside.
+    let mut pending: HashMap<usize, Bitset> = HashMap::new();
+    for (i, id) in order.iter().enumerate() {
+        let size = filesystem.size(*id);
+        if deletable(*id) && size <= limit {
+            pending
+                .entry(end[i])
+                .or_insert_with(|| Bitset::new(limit + 1))
+                .or_shifted(&current, size);
+        }
+        if let Some(arriving) = pending.remove(&(i + 1)) {
+            for total in current.merge_new(&arriving) {
+                first_seen[total] = i + 1;
+            }
+        }
+    }
+    let best_total = (need..=limit)
+        .find(|total| current.contains(*total))
+        .expect("BUG: the best single deletion should be possible");
+
+    // Walk back from the end. If the total was already possible at the previous position, we
+    // didn't delete that node. Otherwise, we must have jumped here by deleting something.
+    let mut deletions = vec![];
+    let (mut i, mut total) = (order.len(), best_total);
+    while total > 0 {
+        if first_seen[total] < i {
+            i -= 1;
+            continue;
+        }
+        let from = (0..i)
+            .rev()
+            .find(|from| {
+                let size = filesystem.size(order[*from]);
+                end[*from] == i
+                    && deletable(order[*from])
+                    && size <= total
+                    && first_seen[total - size] <= *from
+            })
+            .expect("BUG: total appeared from nowhere");
+        deletions.push(order[from]);
+        total -= filesystem.size(order[from]);
+        i = from;
+    }
+    deletions.reverse();
+    Some(plan(deletions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Lcg;
+    use crate::Day7;
+    use aoc_common::Solution;
+
+    fn paths(filesystem: &Filesystem, plan: &Plan) -> Vec<String> {
+        plan.deletions
+            .iter()
+            .map(|id| filesystem.path(*id))
+            .collect()
+    }
+
+    // The smallest total by trying every set of nodes.
+    fn brute_force(filesystem: &Filesystem, need: usize, dirs_only: bool) -> Option<usize> {
+        let nodes: Vec<NodeId> = filesystem
+            .descendants(filesystem.root())
+            .into_iter()
+            .filter(|id| !dirs_only || filesystem.is_dir(*id))
+            .collect();
+        (0..1_u32 << nodes.len())
+            .filter_map(|mask| {
+                let chosen: Vec<NodeId> = (0..nodes.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| nodes[i])
+                    .collect();
+                let nested = chosen.iter().any(|a| {
+                    chosen
+                        .iter()
+                        .any(|b| a != b && filesystem.is_inside(*a, *b))
+                });
+                let total: usize = chosen.iter().map(|id| filesystem.size(*id)).sum();
+                (!nested && total >= need).then_some(total)
+            })
+            .min()
+    }
+
+    #[test]
+    fn example() {
+        let filesystem = Day7::parse_example().unwrap();
+        let plan = |objective, dirs_only| {
+            plan_cleanup(&filesystem, 70_000_000, 30_000_000, objective, dirs_only).unwrap()
+        };
+
+        // Part 2.
+        let part2 = plan(Objective::FewestDeletions, true);
+        assert_eq!(paths(&filesystem, &part2), vec!["/d"]);
+        assert_eq!(part2.freed, 24933642);
+
+        let fewest = plan(Objective::FewestDeletions, false);
+        assert_eq!(paths(&filesystem, &fewest), vec!["/c.dat"]);
+
+        let smallest = plan(Objective::SmallestTotal, false);
+        assert_eq!(smallest.free_before, 21618835);
+        assert_eq!(
+            smallest.freed,
+            brute_force(&filesystem, 8381165, false).unwrap()
+        );
+        assert!(smallest.free_after >= 30_000_000);
+        let total: usize = smallest
+            .deletions
+            .iter()
+            .map(|id| filesystem.size(*id))
+            .sum();
+        assert_eq!(total, smallest.freed);
+
+        let smallest_dirs = plan(Objective::SmallestTotal, true);
+        assert_eq!(paths(&filesystem, &smallest_dirs), vec!["/d"]);
+    }
+
+    #[test]
+    fn nothing_to_do_or_nothing_possible() {
+        let filesystem = Day7::parse_example().unwrap();
+        let plan = plan_cleanup(
+            &filesystem,
+            100_000_000,
+            30_000_000,
+            Objective::SmallestTotal,
+            false,
+        )
+        .unwrap();
+        assert!(plan.deletions.is_empty());
+        assert_eq!(plan.free_after, 51618835);
+
+        // Even deleting everything isn't enough.
+        assert_eq!(
+            plan_cleanup(
+                &filesystem,
+                70_000_000,
+                80_000_000,
+                Objective::SmallestTotal,
+                false
+            ),
+            None
+        );
+        // The disk isn't big enough for what's on it.
+        assert_eq!(
+            plan_cleanup(&filesystem, 1000, 0, Objective::SmallestTotal, false),
+            None
+        );
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        // Some made-up filesystems, using a seeded RNG so the test is the same every time.
+        let mut rng = Lcg(12345);
+
+        for _ in 0..30 {
+            let mut filesystem = Filesystem::new();
+            let mut dirs = vec![filesystem.root()];
+            for i in 0..12 {
+                let dir = dirs[rng.below(dirs.len())];
+                let name = i.to_string();
+                if rng.below(3) == 0 {
+                    dirs.push(filesystem.add_subdir(dir, &name));
+                } else {
+                    filesystem.add_file(dir, &name, 1 + rng.below(100));
+                }
+            }
+
+            let used = filesystem.size(filesystem.root());
+            let need = 1 + rng.below(used);
+            for dirs_only in [false, true] {
+                let plan =
+                    plan_cleanup(&filesystem, used, need, Objective::SmallestTotal, dirs_only);
+                assert_eq!(
+                    plan.map(|plan| plan.freed),
+                    brute_force(&filesystem, need, dirs_only)
+                );
+            }
+        }
+    }
+}
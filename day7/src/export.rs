@@ -0,0 +1,550 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+
+use aoc_common::ParseError;
+
+use crate::filesystem::{is_valid_name, Filesystem, NodeId};
+
+// Getting a [Filesystem] in and out of other formats: a JSON tree, and real directories on disk.
+//
+// The JSON has an object for each node. Directories have a list of children, and files have a
+// size instead:
+//
+//   {"name": "/", "children": [{"name": "b.txt", "size": 14848514}, ...]}
+//
+// The JSON parser is hand-rolled and only knows about the bits of JSON that this needs: objects,
+// arrays, strings and non-negative integers.
+
+pub fn to_json(filesystem: &Filesystem) -> String {
+    let mut json = String::new();
+    write_node(filesystem, filesystem.root(), 0, &mut json);
+    json.push('\n');
+    json
+}
+
+fn write_node(filesystem: &Filesystem, id: NodeId, depth: usize, json: &mut String) {
+    let indent = "  ".repeat(depth);
+    let name = if id == filesystem.root() {
+        "/"
+    } else {
+        filesystem.name(id)
+    };
+    json.push_str(&format!("{}{{\"name\": {}", indent, quote(name)));
+    match filesystem.file_size(id) {
+        Some(size) => json.push_str(&format!(", \"size\": {}}}", size)),
+        None if filesystem.children(id).is_empty() => json.push_str(", \"children\": []}"),
+        None => {
+            json.push_str(", \"children\": [\n");
+            for (i, child) in filesystem.children(id).iter().enumerate() {
+                if i > 0 {
+                    json.push_str(",\n");
+                }
+                write_node(filesystem, *child, depth + 1, json);
+            }
+            json.push_str(&format!("\n{}]}}", indent));
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[derive(Debug)]
+enum Json {
+    Object(Vec<(String, Value)>),
+    Array(Vec<Value>),
+    String(String),
+    Number(usize),
+}
+
+// A JSON value, and where it started, so that errors about the tree can point at it.
+#[derive(Debug)]
+struct Value {
+    json: Json,
+    line: usize,
+    column: usize,
+}
+
+impl Value {
+    fn error(&self, text: impl Into<String>, expected: impl Into<String>) -> ParseError {
+        ParseError::at_column(self.column, text, expected).at_line(self.line)
+    }
+
+    fn describe(&self) -> &'static str {
+        match self.json {
+            Json::Object(_) => "an object",
+            Json::Array(_) => "an array",
+            Json::String(_) => "a string",
+            Json::Number(_) => "a number",
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.next();
+        }
+    }
+
+    fn error(&mut self, expected: &str) -> ParseError {
+        let text = self.peek().map(String::from).unwrap_or_default();
+        ParseError::at_column(self.column, text, expected).at_line(self.line)
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.next();
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{}'", c)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ParseError> {
+        self.skip_whitespace();
+        let (line, column) = (self.line, self.column);
+        let json = match self.peek() {
+            Some('{') => Json::Object(self.object()?),
+            Some('[') => Json::Array(self.array()?),
+            Some('"') => Json::String(self.string()?),
+            Some('0'..='9') => Json::Number(self.number()?),
+            _ => return Err(self.error("an object, array, string or number")),
+        };
+        Ok(Value { json, line, column })
+    }
+
+    // [open] and [close] are the brackets, and [item] parses whatever's between the commas.
+    fn list<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        self.expect(open)?;
+        let mut items = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(close) {
+            self.next();
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.next();
+                }
+                Some(c) if c == close => {
+                    self.next();
+                    return Ok(items);
+                }
+                _ => return Err(self.error(&format!("',' or '{}'", close))),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Vec<(String, Value)>, ParseError> {
+        self.list('{', '}', |parser| {
+            parser.skip_whitespace();
+            if parser.peek() != Some('"') {
+                return Err(parser.error("a string"));
+            }
+            let key = parser.string()?;
+            parser.expect(':')?;
+            Ok((key, parser.value()?))
+        })
+    }
+
+    fn array(&mut self) -> Result<Vec<Value>, ParseError> {
+        self.list('[', ']', |parser| parser.value())
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error("'\"'")),
+                Some('"') => return Ok(s),
+                Some('\\') => s.push(self.escape()?),
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, ParseError> {
+        let c = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.next();
+                let mut code = self.hex()?;
+                // Anything outside the BMP is written as a pair of surrogates.
+                if (0xd800..0xdc00).contains(&code) {
+                    self.expect('\\')?;
+                    self.expect('u')?;
+                    let low = self.hex()?;
+                    if !(0xdc00..0xe000).contains(&low) {
+                        return Err(self.error("the second half of a surrogate pair"));
+                    }
+                    code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                }
+                return char::from_u32(code).ok_or_else(|| self.error("a valid \\u escape"));
+            }
+            _ => return Err(self.error("an escape, like \\n or \\\"")),
+        };
+        self.next();
+        Ok(c)
+    }
+
+    fn hex(&mut self) -> Result<u32, ParseError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .peek()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("a hex digit"))?;
+            self.next();
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<usize, ParseError> {
+        let (line, column) = (self.line, self.column);
+        let mut digits = String::new();
+        while let Some(c) = self.peek().filter(char::is_ascii_digit) {
+            digits.push(c);
+            self.next();
+        }
+        digits.parse().map_err(|_| {
+            ParseError::at_column(column, digits.as_str(), "a size that fits in a usize")
+                .at_line(line)
+        })
+    }
+}
+
+fn parse_json(s: &str) -> Result<Value, ParseError> {
+    let mut parser = Parser {
+        chars: s.chars().peekable(),
+        line: 1,
+        column: 1,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.peek() {
+        None => Ok(value),
+        Some(_) => Err(parser.error("the end of the input")),
+    }
+}
+
+pub fn from_json(s: &str) -> Result<Filesystem, ParseError> {
+    let root = parse_json(s)?;
+    let mut filesystem = Filesystem::new();
+    let (_, children) = node_fields(&root)?;
+    let root_id = filesystem.root();
+    let Kind::Dir(children) = children else {
+        return Err(root.error("a file", "the root to be a directory"));
+    };
+    add_children(&mut filesystem, root_id, children)?;
+    Ok(filesystem)
+}
+
+enum Kind<'a> {
+    Dir(&'a [Value]),
+    File(&'a Value),
+}
+
+// The name of a node, and either its children or its size.
+fn node_fields(value: &Value) -> Result<(&Value, Kind<'_>), ParseError> {
+    let Json::Object(fields) = &value.json else {
+        return Err(value.error(value.describe(), "an object for each file or directory"));
+    };
+    let field = |key: &str| fields.iter().find(|(k, _)| k == key).map(|(_, v)| v);
+    if let Some((key, unknown)) = fields
+        .iter()
+        .find(|(k, _)| !matches!(k.as_str(), "name" | "children" | "size"))
+    {
+        return Err(unknown.error(key.as_str(), "only name, children and size"));
+    }
+
+    let name = field("name").ok_or_else(|| value.error("", "a name"))?;
+    match (field("children"), field("size")) {
+        (Some(children), None) => match &children.json {
+            Json::Array(children) => Ok((name, Kind::Dir(children))),
+            _ => Err(children.error(children.describe(), "an array of children")),
+        },
+        (None, Some(size)) => Ok((name, Kind::File(size))),
+        (Some(_), Some(_)) => Err(value.error(
+            "both children and a size",
+            "a directory with children, or a file with a size",
+        )),
+        (None, None) => Err(value.error("", "a directory with children, or a file with a size")),
+    }
+}
+
+fn add_children(
+    filesystem: &mut Filesystem,
+    dir: NodeId,
+    children: &[Value],
+) -> Result<(), ParseError> {
+    for child in children {
+        let (name_value, kind) = node_fields(child)?;
+        let name = match &name_value.json {
+            Json::String(name) if is_valid_name(name) => name,
+            Json::String(name) => {
+                return Err(name_value.error(name.as_str(), "a name that isn't a path"))
+            }
+            _ => return Err(name_value.error(name_value.describe(), "a name")),
+        };
+        if filesystem.child(dir, name).is_some() {
+            return Err(name_value.error(name.as_str(), "names in a directory to be unique"));
+        }
+
+        match kind {
+            Kind::Dir(grandchildren) => {
+                let subdir = filesystem.add_subdir(dir, name);
+                add_children(filesystem, subdir, grandchildren)?;
+            }
+            Kind::File(size) => match size.json {
+                Json::Number(size) => {
+                    filesystem.add_file(dir, name, size);
+                }
+                _ => return Err(size.error(size.describe(), "a size")),
+            },
+        }
+    }
+    Ok(())
+}
+
+// Recreate the filesystem under [target], which has to be empty (or not exist yet). Files are
+// created with [File::set_len], so on most filesystems they're sparse and don't take up any
+// real space, however big the puzzle says they are.
+pub fn materialise(filesystem: &Filesystem, target: &Path) -> io::Result<()> {
+    // The transcript can call things whatever it likes, so don't let it escape [target]. This has
+    // to happen before anything gets written, so that a bad name doesn't leave half a tree behind.
+    for id in filesystem
+        .descendants(filesystem.root())
+        .into_iter()
+        .skip(1)
+    {
+        if !is_valid_name(filesystem.name(id)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Can't create {:?}", filesystem.path(id)),
+            ));
+        }
+    }
+
+    fs::create_dir_all(target)?;
+    if fs::read_dir(target)?.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} isn't empty", target.display()),
+        ));
+    }
+    write_children(filesystem, filesystem.root(), target)
+}
+
+fn write_children(filesystem: &Filesystem, dir: NodeId, path: &Path) -> io::Result<()> {
+    for child in filesystem.children(dir) {
+        let child_path = path.join(filesystem.name(*child));
+        match filesystem.file_size(*child) {
+            Some(size) => File::create_new(&child_path)?.set_len(size as u64)?,
+            None => {
+                fs::create_dir(&child_path)?;
+                write_children(filesystem, *child, &child_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Read a real directory into a [Filesystem], using each file's apparent size, like [ls] shows.
+// Anything that isn't a plain file or directory (symlinks, sockets, ...) is skipped, and
+// children are added in name order so that scanning the same tree twice gives the same result.
+pub fn scan(path: &Path) -> io::Result<Filesystem> {
+    let mut filesystem = Filesystem::new();
+    let root = filesystem.root();
+    scan_dir(&mut filesystem, root, path)?;
+    Ok(filesystem)
+}
+
+fn scan_dir(filesystem: &mut Filesystem, dir: NodeId, path: &Path) -> io::Result<()> {
+    let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name().into_string().map_err(|name| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{:?} isn't valid UTF-8", name),
+            )
+        })?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            let subdir = filesystem.add_subdir(dir, &name);
+            scan_dir(filesystem, subdir, &entry.path())?;
+        } else if file_type.is_file() {
+            filesystem.add_file(dir, &name, entry.metadata()?.len() as usize);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sizes;
+    use crate::Day7;
+    use aoc_common::Solution;
+
+    #[test]
+    fn json() {
+        let mut filesystem = Filesystem::new();
+        let root = filesystem.root();
+        let a = filesystem.add_subdir(root, "a");
+        filesystem.add_file(a, "b \"quoted\".txt", 10);
+        filesystem.add_subdir(a, "empty");
+        filesystem.add_file(root, "c", 1);
+
+        let json = to_json(&filesystem);
+        assert_eq!(
+            json,
+            r#"{"name": "/", "children": [
+  {"name": "a", "children": [
+    {"name": "b \"quoted\".txt", "size": 10},
+    {"name": "empty", "children": []}
+  ]},
+  {"name": "c", "size": 1}
+]}
+"#
+        );
+        let imported = from_json(&json).unwrap();
+        assert_eq!(to_json(&imported), json);
+        assert_eq!(imported.size(imported.root()), 11);
+
+        let example = Day7::parse_example().unwrap();
+        let imported = from_json(&to_json(&example)).unwrap();
+        assert_eq!(sizes(&imported), sizes(&example));
+
+        // It doesn't care about layout, and does know about escapes.
+        let compact = r#"{"name":"/","children":[{"size":5,"name":"é\n\ud83d\udcc1"}]}"#;
+        let compact = from_json(compact).unwrap();
+        assert_eq!(compact.lookup("/é\n📁").map(|id| compact.size(id)), Some(5));
+    }
+
+    #[test]
+    fn bad_json() {
+        let error = |json: &str| from_json(json).unwrap_err().to_string();
+        assert_eq!(
+            error("{\"name\": \"/\",\n \"children\": [1]}"),
+            "line 2, column 15: expected an object for each file or directory, found \"a number\""
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "size": 3}"#),
+            "line 1, column 1: expected the root to be a directory, found \"a file\""
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "children": [{"name": "..", "size": 3}]}"#),
+            "line 1, column 37: expected a name that isn't a path, found \"..\""
+        );
+        assert_eq!(
+            error(
+                r#"{"name": "/", "children": [{"name": "x", "size": 3}, {"name": "x", "children": []}]}"#
+            ),
+            "line 1, column 63: expected names in a directory to be unique, found \"x\""
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "children": [{"name": "x"}]}"#),
+            "line 1, column 28: expected a directory with children, or a file with a size, found nothing"
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "children": [], "owner": "me"}"#),
+            "line 1, column 40: expected only name, children and size, found \"owner\""
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "children": [] "#),
+            "line 1, column 30: expected ',' or '}', found nothing"
+        );
+        assert_eq!(
+            error(r#"{"name": "/", "children": [{"name": "x", "size": -3}]}"#),
+            "line 1, column 50: expected an object, array, string or number, found \"-\""
+        );
+    }
+
+    #[test]
+    fn disk_round_trip() {
+        let target = std::env::temp_dir().join(format!("day7-export-{}", std::process::id()));
+        let example = Day7::parse_example().unwrap();
+        materialise(&example, &target).unwrap();
+
+        let scanned = scan(&target).unwrap();
+        assert_eq!(sizes(&scanned), sizes(&example));
+        assert_eq!(scanned.size(scanned.root()), 48381165);
+
+        // It won't write over something that's already there.
+        assert_eq!(
+            materialise(&example, &target).unwrap_err().kind(),
+            io::ErrorKind::AlreadyExists
+        );
+        fs::remove_dir_all(&target).unwrap();
+    }
+
+    #[test]
+    fn bad_names_write_nothing() {
+        let target = std::env::temp_dir().join(format!("day7-bad-names-{}", std::process::id()));
+        let mut filesystem = Filesystem::new();
+        let root = filesystem.root();
+        let a = filesystem.add_subdir(root, "a");
+        filesystem.add_file(a, "b", 1);
+        filesystem.add_file(root, "..", 2);
+
+        let error = materialise(&filesystem, &target).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(error.to_string(), "Can't create \"/..\"");
+        assert!(!target.exists());
+    }
+}
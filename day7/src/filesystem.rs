@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+// The filesystem is a tree, with every node (file or directory) kept in one big Vec and referred
+// to by its index. Nodes point to their parent and children by index too, which keeps the borrow
+// checker happy without having to look everything up by its full path.
+//
+// Every node also keeps its total size, which gets updated on the way up to the root whenever
+// something changes. Removed nodes stay in the Vec, they just can't be reached from the root any
+// more. That wastes a bit of space, but means a [NodeId] never gets reused for something else.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+// Names end up as path components, both in [Filesystem::resolve] and on disk, so anything that
+// lets people pick their own names should check them with this.
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !matches!(name, "" | "." | "..") && !name.contains(['/', '\0'])
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    name: String,
+    parent: Option<NodeId>,
+    // Always empty for files.
+    children: Vec<NodeId>,
+    // The same children again, by name, so that [Filesystem::child] doesn't have to look through
+    // all of them. Big directories would be quadratic to build otherwise.
+    names: HashMap<String, NodeId>,
+    // [None] for directories.
+    file_size: Option<usize>,
+    // The file size, or for directories, the total of everything in them.
+    size: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Filesystem {
+    nodes: Vec<Node>,
+}
+
+impl Default for Filesystem {
+    fn default() -> Self {
+        Filesystem::new()
+    }
+}
+
+impl Filesystem {
+    pub fn new() -> Self {
+        let root = Node {
+            name: String::new(),
+            parent: None,
+            children: vec![],
+            names: HashMap::new(),
+            file_size: None,
+            size: 0,
+        };
+        Filesystem { nodes: vec![root] }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.node(id).name
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        self.node(id).file_size.is_none()
+    }
+
+    // The size of a file, or [None] for a directory.
+    pub fn file_size(&self, id: NodeId) -> Option<usize> {
+        self.node(id).file_size
+    }
+
+    // The size of a file, or the total size of a directory.
+    pub fn size(&self, id: NodeId) -> usize {
+        self.node(id).size
+    }
+
+    pub fn parent_dir(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    pub fn children(&self, dir: NodeId) -> &[NodeId] {
+        &self.node(dir).children
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.node(dir).names.get(name).copied()
+    }
+
+    fn add_node(&mut self, dir: NodeId, name: &str, file_size: Option<usize>) -> NodeId {
+        assert!(self.is_dir(dir), "BUG: adding {} to a file", name);
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(dir),
+            children: vec![],
+            names: HashMap::new(),
+            file_size,
+            size: 0,
+        });
+        self.nodes[dir.0].children.push(id);
+        self.nodes[dir.0].names.insert(name.to_string(), id);
+        if let Some(size) = file_size {
+            self.grow(id, size);
+        }
+        id
+    }
+
+    // Add [size] to the total for [id] and everything above it.
+    fn grow(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id.0].size += size;
+            current = self.parent_dir(id);
+        }
+    }
+
+    fn shrink(&mut self, id: NodeId, size: usize) {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            self.nodes[id.0].size -= size;
+            current = self.parent_dir(id);
+        }
+    }
+
+    // Returns the existing subdirectory if there already is one with this name.
+    pub fn add_subdir(&mut self, dir: NodeId, name: &str) -> NodeId {
+        match self.child(dir, name) {
+            Some(existing) => {
+                assert!(self.is_dir(existing), "BUG: {} is already a file", name);
+                existing
+            }
+            None => self.add_node(dir, name, None),
+        }
+    }
+
+    pub fn add_file(&mut self, dir: NodeId, name: &str, size: usize) -> NodeId {
+        match self.child(dir, name) {
+            Some(existing) => {
+                if self.file_size(existing) != Some(size) {
+                    panic!("File already added, with a different size")
+                }
+                existing
+            }
+            None => self.add_node(dir, name, Some(size)),
+        }
+    }
+
+    pub fn set_file_size(&mut self, file: NodeId, size: usize) {
+        let old_size = self
+            .file_size(file)
+            .expect("BUG: [set_file_size] called on a directory");
+        self.nodes[file.0].file_size = Some(size);
+        self.shrink(file, old_size);
+        self.grow(file, size);
+    }
+
+    // Is [id] inside [ancestor] (or the same thing)?
+    pub fn is_inside(&self, id: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor {
+                return true;
+            }
+            current = self.parent_dir(id);
+        }
+        false
+    }
+
+    fn detach(&mut self, id: NodeId) {
+        let parent = self
+            .parent_dir(id)
+            .expect("BUG: tried to remove or move the root");
+        self.shrink(parent, self.size(id));
+        let name = self.nodes[id.0].name.clone();
+        let parent_node = &mut self.nodes[parent.0];
+        parent_node.children.retain(|child| *child != id);
+        parent_node.names.remove(&name);
+        self.nodes[id.0].parent = None;
+    }
+
+    // Remove [id], and everything in it if it's a directory.
+    pub fn remove(&mut self, id: NodeId) {
+        self.detach(id);
+    }
+
+    // Move [id] into [dir], calling it [name].
+    pub fn move_node(&mut self, id: NodeId, dir: NodeId, name: &str) {
+        assert!(self.is_dir(dir), "BUG: moving {} into a file", name);
+        assert!(
+            !self.is_inside(dir, id),
+            "BUG: moving {} inside itself",
+            self.name(id)
+        );
+        assert!(
+            self.child(dir, name).is_none_or(|existing| existing == id),
+            "BUG: {} already exists",
+            name
+        );
+
+        self.detach(id);
+        let node = &mut self.nodes[id.0];
+        node.name = name.to_string();
+        node.parent = Some(dir);
+        self.nodes[dir.0].children.push(id);
+        self.nodes[dir.0].names.insert(name.to_string(), id);
+        self.grow(dir, self.size(id));
+    }
+
+    // The path from the root, e.g. "/a/e". The root is just "/".
+    pub fn path(&self, id: NodeId) -> String {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.parent_dir(current) {
+            names.push(self.name(current));
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // Follow [path] from [from]. Paths starting with '/' are from the root instead, and ".." goes
+    // up a level, as usual.
+    pub fn resolve(&self, from: NodeId, path: &str) -> Option<NodeId> {
+        let mut current = if path.starts_with('/') {
+            self.root()
+        } else {
+            from
+        };
+        for name in path.split('/').filter(|name| !name.is_empty()) {
+            current = match name {
+                "." => current,
+                ".." => self.parent_dir(current)?,
+                _ => self.child(current, name)?,
+            };
+        }
+        Some(current)
+    }
+
+    // Look up an absolute path, like "/a/e".
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        self.resolve(self.root(), path)
+    }
+
+    // Every node under [id] (including itself), with parents before their children.
+    pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = vec![];
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            result.push(current);
+            stack.extend(self.children(current).iter().rev());
+        }
+        result
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.descendants(self.root())
+            .into_iter()
+            .filter(|id| self.is_dir(*id))
+    }
+
+    // The total size of everything in each directory, including subdirectories.
+    pub fn total_dir_sizes(&self) -> HashMap<NodeId, usize> {
+        self.dirs().map(|id| (id, self.size(id))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // /
+    //   a/
+    //     b.txt (10)
+    //     c/
+    //       d (5)
+    //   e (1)
+    fn example() -> Filesystem {
+        let mut filesystem = Filesystem::new();
+        let root = filesystem.root();
+        let a = filesystem.add_subdir(root, "a");
+        filesystem.add_file(a, "b.txt", 10);
+        let c = filesystem.add_subdir(a, "c");
+        filesystem.add_file(c, "d", 5);
+        filesystem.add_file(root, "e", 1);
+        filesystem
+    }
+
+    #[test]
+    fn paths() {
+        let filesystem = example();
+        let d = filesystem.lookup("/a/c/d").unwrap();
+        assert_eq!(filesystem.path(d), "/a/c/d");
+        assert_eq!(filesystem.name(d), "d");
+        assert_eq!(filesystem.file_size(d), Some(5));
+        assert_eq!(filesystem.path(filesystem.root()), "/");
+        assert_eq!(filesystem.lookup("/"), Some(filesystem.root()));
+        assert_eq!(filesystem.lookup("/a/c/"), filesystem.lookup("/a/c"));
+        assert_eq!(filesystem.lookup("/a/x"), None);
+
+        let c = filesystem.lookup("/a/c").unwrap();
+        assert!(filesystem.is_dir(c));
+        assert_eq!(filesystem.resolve(c, "d"), Some(d));
+        assert_eq!(
+            filesystem.resolve(c, "../b.txt"),
+            filesystem.lookup("/a/b.txt")
+        );
+        assert_eq!(filesystem.resolve(c, "./../.."), Some(filesystem.root()));
+        assert_eq!(filesystem.resolve(c, "/e"), filesystem.lookup("/e"));
+        assert_eq!(filesystem.resolve(c, "../../.."), None);
+        assert_eq!(filesystem.parent_dir(filesystem.root()), None);
+    }
+
+    #[test]
+    fn adding_twice_gives_the_same_node() {
+        let mut filesystem = example();
+        let root = filesystem.root();
+        let a = filesystem.lookup("/a").unwrap();
+        assert_eq!(filesystem.add_subdir(root, "a"), a);
+        assert_eq!(
+            filesystem.add_file(a, "b.txt", 10),
+            filesystem.lookup("/a/b.txt").unwrap()
+        );
+        assert_eq!(filesystem.children(a).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "different size")]
+    fn conflicting_file_sizes() {
+        let mut filesystem = example();
+        let root = filesystem.root();
+        filesystem.add_file(root, "e", 2);
+    }
+
+    #[test]
+    fn sizes() {
+        let filesystem = example();
+        let sizes = filesystem.total_dir_sizes();
+        let size = |path| sizes[&filesystem.lookup(path).unwrap()];
+        assert_eq!(sizes.len(), 3);
+        assert_eq!(size("/"), 16);
+        assert_eq!(size("/a"), 15);
+        assert_eq!(size("/a/c"), 5);
+
+        let dirs: Vec<String> = filesystem.dirs().map(|id| filesystem.path(id)).collect();
+        assert_eq!(dirs, vec!["/", "/a", "/a/c"]);
+    }
+
+    // Checks the cached sizes against adding everything up from scratch.
+    fn check_sizes(filesystem: &Filesystem) {
+        for id in filesystem.descendants(filesystem.root()) {
+            let total: usize = filesystem
+                .descendants(id)
+                .into_iter()
+                .filter_map(|id| filesystem.file_size(id))
+                .sum();
+            assert_eq!(filesystem.size(id), total, "{}", filesystem.path(id));
+        }
+    }
+
+    #[test]
+    fn changes_update_sizes() {
+        let mut filesystem = example();
+        let root = filesystem.root();
+        let a = filesystem.lookup("/a").unwrap();
+        let c = filesystem.lookup("/a/c").unwrap();
+        let d = filesystem.lookup("/a/c/d").unwrap();
+
+        filesystem.set_file_size(d, 7);
+        assert_eq!(filesystem.size(a), 17);
+        check_sizes(&filesystem);
+
+        filesystem.move_node(c, root, "f");
+        assert_eq!(filesystem.path(d), "/f/d");
+        assert_eq!(filesystem.lookup("/a/c"), None);
+        assert_eq!(filesystem.size(a), 10);
+        assert_eq!(filesystem.size(root), 18);
+        check_sizes(&filesystem);
+
+        // Renaming without moving.
+        filesystem.move_node(c, root, "g");
+        assert_eq!(filesystem.lookup("/g/d"), Some(d));
+        assert_eq!(filesystem.child(root, "f"), None);
+        check_sizes(&filesystem);
+
+        filesystem.remove(a);
+        assert_eq!(filesystem.lookup("/a"), None);
+        assert_eq!(filesystem.size(root), 8);
+        assert_eq!(filesystem.total_dir_sizes().len(), 2);
+        check_sizes(&filesystem);
+
+        // The name's free again once it's been removed.
+        assert_ne!(filesystem.add_subdir(root, "a"), a);
+
+        assert!(filesystem.is_inside(d, c));
+        assert!(filesystem.is_inside(c, c));
+        assert!(!filesystem.is_inside(c, d));
+    }
+
+    #[test]
+    #[should_panic(expected = "inside itself")]
+    fn cant_move_a_dir_inside_itself() {
+        let mut filesystem = example();
+        let a = filesystem.lookup("/a").unwrap();
+        let c = filesystem.lookup("/a/c").unwrap();
+        filesystem.move_node(a, c, "a");
+    }
+}
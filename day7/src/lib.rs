@@ -0,0 +1,107 @@
+use aoc_common::{Answer, ParseError, Solution};
+
+pub mod cleanup;
+pub mod export;
+mod filesystem;
+pub mod parser;
+pub mod shell;
+#[cfg(test)]
+mod test_util;
+pub mod transcript;
+
+use cleanup::{plan_cleanup, Objective};
+pub use filesystem::{Filesystem, NodeId};
+use parser::parse_transcript;
+
+// From part 2.
+pub const DISK_SIZE: usize = 70_000_000;
+pub const REQUIRED_FREE: usize = 30_000_000;
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Filesystem;
+
+    // The puzzle input should be perfectly clean, so anything [parse_transcript] would warn about
+    // is an error here.
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let (filesystem, report) = parse_transcript(s);
+        match report.warnings.first() {
+            Some(warning) => Err(warning.to_parse_error()),
+            None => Ok(filesystem),
+        }
+    }
+
+    // Part 1: Total size of all dirs with cumulative weight <= 10^5
+    fn part1(filesystem: &Filesystem) -> Answer {
+        let total_dir_sizes = filesystem.total_dir_sizes();
+
+        let total: usize = total_dir_sizes
+            .values()
+            .copied()
+            .filter(|x| *x <= 100000)
+            .sum();
+
+        total.into()
+    }
+
+    // Part 2: Delete the smallest directory such that the total free size is at least 3e7
+    fn part2(filesystem: &Filesystem) -> Answer {
+        let plan = plan_cleanup(
+            filesystem,
+            DISK_SIZE,
+            REQUIRED_FREE,
+            Objective::FewestDeletions,
+            true,
+        );
+        match plan {
+            Some(plan) => plan.freed.into(),
+            // Can't happen with the puzzle input, but made-up filesystems can be too big for the
+            // disk.
+            None => "no directory is big enough".to_string().into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day7::parse_example().unwrap();
+        assert_eq!(Day7::part1(&input), Answer::Int(95437));
+        assert_eq!(Day7::part2(&input), Answer::Int(24933642));
+    }
+
+    #[test]
+    fn dir_sizes() {
+        let filesystem = Day7::parse_example().unwrap();
+        let sizes = filesystem.total_dir_sizes();
+        let size = |path| sizes[&filesystem.lookup(path).unwrap()];
+
+        assert_eq!(sizes.len(), 4);
+        assert_eq!(size("/"), 48381165);
+        assert_eq!(size("/a"), 94853);
+        assert_eq!(size("/a/e"), 584);
+        assert_eq!(size("/d"), 24933642);
+    }
+
+    #[test]
+    fn part2_on_other_disks() {
+        // Already enough space.
+        let small = Day7::parse("$ cd /\n$ ls\n100 a\n").unwrap();
+        assert_eq!(Day7::part2(&small), Answer::Int(0));
+
+        let mut too_big = Filesystem::new();
+        let root = too_big.root();
+        too_big.add_file(root, "a", DISK_SIZE + 1);
+        assert_eq!(
+            Day7::part2(&too_big),
+            Answer::Text("no directory is big enough".to_string())
+        );
+    }
+}
@@ -1,226 +1,261 @@
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::fs::read_to_string;
-
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-struct DirPath(String);
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use aoc_common::cli::{usage_error, Args, InputSource};
+use aoc_common::solution::{load, print_answers};
+use aoc_common::Solution;
+use day7::cleanup::{plan_cleanup, Objective};
+use day7::export::{from_json, materialise, scan, to_json};
+use day7::parser::parse_transcript;
+use day7::shell::Shell;
+use day7::transcript::{random_filesystem, write_transcript, Options, Order};
+use day7::{Day7, Filesystem, DISK_SIZE, REQUIRED_FREE};
+
+const USAGE: &str = "\
+Usage: day7 [<source>] [--shell | --json | --du | --materialise <dir>]
+       day7 [<source>] --plan [--objective smallest|fewest] [--dirs-only]
+            [--disk-size <n>] [--required <n>]
+       day7 [<source>] --transcript [--order dfs|bfs|random] [--relist] [--seed <n>]
+
+<source> is one of:
+  <input> | - | --example   a transcript, like the puzzle input, optionally with --lenient
+  --from-json <path>        a filesystem written out by --json (- for stdin)
+  --scan <dir>              a real directory on disk
+  --generate <n>            a made-up filesystem with <n> files and directories
+
+--lenient carries on past anything odd in the transcript (other commands, files that change
+size, ...), printing a warning for each one and a summary at the end.
+
+--json writes the filesystem out as JSON, and --du lists the total size of every directory.
+--materialise creates it all under <dir>, which has to be empty, using sparse files.
+
+--transcript writes out the cd and ls commands that would rebuild the filesystem, visiting the
+directories in the given order (dfs by default). --relist lists directories again whenever it
+passes back through them. --seed is for --order random and --generate, and defaults to 0.
+
+--shell rebuilds the filesystem from <source>, then lets you explore it with commands
+read from stdin. Type help to see what they are.
+
+--plan works out what to delete to get <n> free (default 30000000) on a disk of the given
+size (default 70000000). Nothing in the plan is inside anything else. The objective is either
+the smallest total size (the default), or the fewest deletions. With --dirs-only, only whole
+directories get deleted, like in part 2.";
+
+struct PlanOptions {
+    objective: Objective,
+    dirs_only: bool,
+    disk_size: usize,
+    required_free: usize,
+}
 
-impl DirPath {
-    fn extend(&self, next: &str) -> DirPath {
-        DirPath(self.0.clone() + next + "/")
+fn parse_plan_options(args: &mut Args) -> Result<Option<PlanOptions>, String> {
+    let objective = args.value("--objective")?;
+    let dirs_only = args.flag("--dirs-only");
+    let disk_size = args.parsed_value("--disk-size")?;
+    let required_free = args.parsed_value("--required")?;
+    if !args.flag("--plan") {
+        if objective.is_some() || dirs_only || disk_size.is_some() || required_free.is_some() {
+            return Err(
+                "--objective, --dirs-only, --disk-size and --required only make sense with --plan"
+                    .to_string(),
+            );
+        }
+        return Ok(None);
     }
 
-    fn root_path() -> Self {
-        DirPath("/".to_string())
-    }
+    let objective = match objective.as_deref() {
+        None | Some("smallest") => Objective::SmallestTotal,
+        Some("fewest") => Objective::FewestDeletions,
+        Some(objective) => return Err(format!("Unknown objective {}", objective)),
+    };
+    Ok(Some(PlanOptions {
+        objective,
+        dirs_only,
+        disk_size: disk_size.unwrap_or(DISK_SIZE),
+        required_free: required_free.unwrap_or(REQUIRED_FREE),
+    }))
 }
 
-// In OCaml, I'd probably want to have subdirs contain a reference to [Dir] instead, rather than just a path
-// I couldn't work out how to get that in Rust, you get into borrowchecker hell, so I'm keeping just a reference
-// to the name of the subdirectory, and have to keep swapping between that and the HashMap.
-
-#[derive(Debug)]
-struct Dir {
-    // path is represented as e.g. /a/b/c
-    files: HashMap<String, usize>,
-    subdirs: HashSet<DirPath>,
-    parent: Option<DirPath>,
-}
+fn print_plan(filesystem: &Filesystem, options: &PlanOptions) {
+    let Some(plan) = plan_cleanup(
+        filesystem,
+        options.disk_size,
+        options.required_free,
+        options.objective,
+        options.dirs_only,
+    ) else {
+        println!("No plan frees up enough space");
+        return;
+    };
 
-#[derive(Debug)]
-struct Filesystem {
-    all_dirs: HashMap<DirPath, Dir>,
+    let paths: Vec<String> = plan
+        .deletions
+        .iter()
+        .map(|id| filesystem.path(*id))
+        .collect();
+    let width = paths.iter().map(String::len).max().unwrap_or(0);
+    println!("Delete {} things:", plan.deletions.len());
+    for (path, id) in paths.iter().zip(&plan.deletions) {
+        println!("  {:<width$}  {}", path, filesystem.size(*id));
+    }
+    println!(
+        "Frees {}, taking free space from {} to {} (wanted {})",
+        plan.freed, plan.free_before, plan.free_after, options.required_free
+    );
 }
 
-impl Filesystem {
-    fn new() -> Self {
-        let root_path = DirPath::root_path();
-
-        let root_dir: Dir = Dir {
-            files: HashMap::new(),
-            subdirs: HashSet::new(),
-            parent: None,
+fn run_shell(mut shell: Shell) {
+    let stdin = io::stdin();
+    let interactive = stdin.is_terminal();
+    let mut lines = stdin.lock().lines();
+    loop {
+        if interactive {
+            print!("{}", shell.prompt());
+            io::stdout().flush().expect("Failed to write to stdout");
+        }
+        let line = match lines.next() {
+            None => break,
+            Some(line) => line.expect("Failed to read stdin"),
         };
-        let mut all_dirs = HashMap::new();
-        all_dirs.insert(root_path, root_dir);
-        Filesystem { all_dirs }
-    }
-
-    fn add_subdir(&mut self, path: &DirPath, name: &str) {
-        let dir = self
-            .all_dirs
-            .get_mut(path)
-            .expect("BUG: [add_subdir] called with non-existent path");
-        let subpath = path.extend(name);
-
-        if !dir.subdirs.contains(&subpath) {
-            dir.subdirs.insert(subpath.clone());
-
-            let subdir: Dir = Dir {
-                files: HashMap::new(),
-                subdirs: HashSet::new(),
-                parent: Some(path.clone()),
-            };
-
-            self.all_dirs.insert(subpath, subdir);
+        if matches!(line.trim(), "exit" | "quit") {
+            break;
         }
-    }
-
-    fn add_file(&mut self, path: &DirPath, name: &str, size: usize) {
-        let dir = self
-            .all_dirs
-            .get_mut(path)
-            .expect("BUG: [add_file] called with non-existent path");
-
-        match dir.files.get(name) {
-            None => {
-                let _ = dir.files.insert(name.to_string(), size);
-            }
-            Some(current_size) => {
-                if size != *current_size {
-                    panic!("File already added, with a different size")
-                }
-            }
+        match shell.run(&line) {
+            Ok(output) => print!("{}", output),
+            Err(e) => eprintln!("{}", e),
         }
     }
+}
 
-    fn parent_dir(&self, path: &DirPath) -> Option<DirPath> {
-        let dir = self
-            .all_dirs
-            .get(path)
-            .expect("BUG: [parent_dir] called with non-existent path");
+enum Mode {
+    Answers,
+    Shell,
+    Plan(PlanOptions),
+    Json,
+    Du,
+    Materialise(PathBuf),
+    Transcript(Options),
+}
 
-        dir.parent.clone()
+fn parse_mode(args: &mut Args, seed: u64) -> Result<Mode, String> {
+    let mut modes = vec![];
+    if args.flag("--shell") {
+        modes.push(Mode::Shell);
     }
-
-    fn total_dir_sizes(&self) -> HashMap<DirPath, usize> {
-        fn populate_for_dir(
-            filesystem: &Filesystem,
-            result: &mut HashMap<DirPath, usize>,
-            path: &DirPath,
-        ) {
-            let dir = filesystem
-                .all_dirs
-                .get(path)
-                .expect("BUG: [populate_for_dir] called with non-existent path");
-
-            let mut total_size = 0;
-
-            // Populate the map recursively for each subdir, and include their weights.
-            for subdir in dir.subdirs.iter() {
-                populate_for_dir(filesystem, result, subdir);
-                total_size += result.get(subdir).unwrap();
-            }
-
-            // Don't forget the files!
-            for size in dir.files.values() {
-                total_size += size;
-            }
-            let _ = result.insert(path.clone(), total_size);
-        }
-
-        let mut result = HashMap::new();
-        populate_for_dir(self, &mut result, &DirPath::root_path());
-        result
+    if let Some(options) = parse_plan_options(args)? {
+        modes.push(Mode::Plan(options));
+    }
+    if args.flag("--json") {
+        modes.push(Mode::Json);
+    }
+    if args.flag("--du") {
+        modes.push(Mode::Du);
+    }
+    if let Some(dir) = args.value("--materialise")? {
+        modes.push(Mode::Materialise(PathBuf::from(dir)));
+    }
+    let order: Option<Order> = args.parsed_value("--order")?;
+    let relist = args.flag("--relist");
+    if args.flag("--transcript") {
+        modes.push(Mode::Transcript(Options {
+            order: order.unwrap_or(Order::DepthFirst),
+            relist,
+            seed,
+        }));
+    } else if order.is_some() || relist {
+        return Err("--order and --relist only make sense with --transcript".to_string());
+    }
+    match modes.len() {
+        0 => Ok(Mode::Answers),
+        1 => Ok(modes.pop().unwrap()),
+        _ => Err(
+            "Pick one of --shell, --plan, --json, --du, --materialise and --transcript".to_string(),
+        ),
     }
 }
-struct ParserState {
-    working_dir: DirPath,
-    in_ls_command: bool,
-}
-
-fn parse_input_into_filesystem(s: Vec<&str>) -> Filesystem {
-    let mut parser_state = ParserState {
-        working_dir: DirPath::root_path(),
-        in_ls_command: false,
-    };
 
-    let mut filesystem = Filesystem::new();
-
-    for input in s.into_iter() {
-        if input == "$ ls" {
-            parser_state.in_ls_command = true;
-        } else if input.starts_with("$ cd ") {
-            parser_state.in_ls_command = false;
-            let subdir = input.strip_prefix("$ cd ").unwrap();
-
-            if subdir == ".." {
-                parser_state.working_dir = filesystem
-                    .parent_dir(&parser_state.working_dir)
-                    .expect("Tried to cd .. from top-level");
-            } else if subdir == "/" {
-                parser_state.working_dir = DirPath::root_path();
-            } else {
-                parser_state.working_dir = parser_state.working_dir.extend(subdir);
-            }
-        } else if input.starts_with('$') {
-            panic!("Unrecognised command: {}", input);
-        } else {
-            // We're not in a command. Better hope we were expecting this.
-            if !parser_state.in_ls_command {
-                panic!("BUG! Saw something that didn't look like a command, but I wasn't expecting output from ls")
+// Rebuild the filesystem from wherever the arguments say.
+fn load_filesystem(mut args: Args, seed: u64) -> Result<Filesystem, String> {
+    let lenient = args.flag("--lenient");
+    let json = args.value("--from-json")?;
+    let dir = args.value("--scan")?;
+    let nodes = args.parsed_value("--generate")?;
+    if lenient && (json.is_some() || dir.is_some() || nodes.is_some()) {
+        usage_error(USAGE, "--lenient is only for transcripts");
+    }
+    match (json, dir, nodes) {
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) | (_, Some(_), Some(_)) => {
+            usage_error(USAGE, "Pick one of --from-json, --scan and --generate")
+        }
+        (None, None, Some(nodes)) => {
+            args.finish().unwrap_or_else(|e| usage_error(USAGE, &e));
+            Ok(random_filesystem(nodes, seed))
+        }
+        (Some(path), None, None) => {
+            args.finish().unwrap_or_else(|e| usage_error(USAGE, &e));
+            let json = InputSource::from_arg(&path).read("")?;
+            from_json(&json).map_err(|e| format!("{}: {}", path, e))
+        }
+        (None, Some(dir), None) => {
+            args.finish().unwrap_or_else(|e| usage_error(USAGE, &e));
+            scan(Path::new(&dir)).map_err(|e| format!("Failed to scan {}: {}", dir, e))
+        }
+        (None, None, None) => {
+            let source = args
+                .input_source(concat!(env!("CARGO_MANIFEST_DIR"), "/src/input"))
+                .and_then(|source| args.finish().map(|()| source))
+                .unwrap_or_else(|e| usage_error(USAGE, &e));
+            if !lenient {
+                return load::<Day7>(&source);
             }
-
-            if input.starts_with("dir") {
-                // We're listing a directory
-                filesystem.add_subdir(
-                    &parser_state.working_dir,
-                    input.strip_prefix("dir ").unwrap(),
-                );
-            } else {
-                // We're listing a file
-                let mut tokens = input.split(' ');
-                let size = tokens
-                    .next()
-                    .expect("BUG: empty line in input?")
-                    .parse::<usize>()
-                    .expect("Couldn't parse size of file as an integer");
-                let name = tokens.next().expect("BUG: No name of file?");
-                match tokens.next() {
-                    Some(_) => panic!("Wasn't expecting any more words in a line"),
-                    None => (),
-                };
-
-                filesystem.add_file(&parser_state.working_dir, name, size);
+            let (filesystem, report) = parse_transcript(&source.read(Day7::EXAMPLE)?);
+            for warning in &report.warnings {
+                eprintln!("{}", warning);
             }
+            eprintln!("{}", report);
+            Ok(filesystem)
         }
     }
-    filesystem
 }
 
 fn main() {
-    let input: String = read_to_string("input").expect("Failed to read input");
-    let lines = input.split('\n').filter(|s| !s.is_empty()).collect();
-
-    let filesystem = parse_input_into_filesystem(lines);
-
-    for (key, value) in filesystem.all_dirs.iter() {
-        println!("{:?}: {:?}", key, value);
+    let mut args = Args::from_env();
+    let seed = args
+        .parsed_value("--seed")
+        .unwrap_or_else(|e| usage_error(USAGE, &e))
+        .unwrap_or(0);
+    let mode = parse_mode(&mut args, seed).unwrap_or_else(|e| usage_error(USAGE, &e));
+    let filesystem = load_filesystem(args, seed).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+
+    match mode {
+        Mode::Answers => print_answers::<Day7>(&filesystem),
+        Mode::Shell => run_shell(Shell::new(filesystem)),
+        Mode::Plan(options) => print_plan(&filesystem, &options),
+        Mode::Json => print!("{}", to_json(&filesystem)),
+        Mode::Du => {
+            let du = Shell::new(filesystem).run("du");
+            print!("{}", du.expect("BUG: du at the root failed"));
+        }
+        Mode::Materialise(dir) => {
+            if let Err(e) = materialise(&filesystem, &dir) {
+                eprintln!(
+                    "Failed to create the filesystem in {}: {}",
+                    dir.display(),
+                    e
+                );
+                process::exit(1)
+            }
+        }
+        Mode::Transcript(options) => match write_transcript(&filesystem, options) {
+            Ok(transcript) => print!("{}", transcript),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1)
+            }
+        },
     }
-
-    let total_dir_sizes = filesystem.total_dir_sizes();
-
-    // Part 1: Total size of all dirs with cumulative weight <= 10^5
-    let total: usize = total_dir_sizes
-        .values()
-        .copied()
-        .filter(|x| *x <= 100000)
-        .sum();
-
-    println!("{}", total);
-
-    // Part 2: Delete the smallest directory such that the total free size is at least 3e7
-    let total_used_size = total_dir_sizes.get(&DirPath::root_path()).unwrap();
-    let required_free_size = 30_000_000;
-    let total_disk_size = 70_000_000;
-    let current_free_size = total_disk_size - total_used_size;
-    let need_to_free_up_at_least = required_free_size - current_free_size;
-
-    let smallest_dir_that_frees_up_enough_space = total_dir_sizes
-        .values()
-        .copied()
-        .filter(|x| *x >= need_to_free_up_at_least)
-        .min()
-        .expect("Oh no! No directory is big enough.");
-    println!("{}", smallest_dir_that_frees_up_enough_space);
 }
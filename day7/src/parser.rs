@@ -0,0 +1,476 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use aoc_common::input::numbered_lines;
+use aoc_common::ParseError;
+
+use crate::filesystem::{is_valid_name, Filesystem, NodeId};
+
+// Rebuilding the filesystem from a transcript of cd and ls commands.
+//
+// Real transcripts can be messier than the puzzle's: other commands, a file that's changed size
+// since the last time it was listed, and so on. The parser carries on past all of those, noting
+// each one as a [Warning], and [Day7::parse] turns the first one into an error. A few things that
+// aren't problems are fine either way:
+//   - cd-ing into a directory that hasn't been listed yet just creates it
+//   - listing a directory again replaces what we knew about it, so anything that's gone from the
+//     new listing gets removed (that one is a warning, since something changed)
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    UnknownCommand(String),
+    OutputOutsideLs(String),
+    // ls printed something that's neither "dir <name>" nor "<size> <name>".
+    BadListing(String),
+    // A name that can't be part of a path, like "." or "a/b".
+    BadName(String),
+    CdAboveRoot,
+    CdIntoFile(String),
+    SizeChanged {
+        path: String,
+        old: usize,
+        new: usize,
+    },
+    // Something that used to be a file is now a directory, or the other way around.
+    KindChanged {
+        path: String,
+        now_dir: bool,
+    },
+    Vanished(String),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnknownCommand(line) => write!(f, "unknown command {:?}, ignoring it", line),
+            Problem::OutputOutsideLs(line) => write!(f, "{:?} isn't from an ls, ignoring it", line),
+            Problem::BadListing(line) => write!(f, "couldn't understand {:?}, ignoring it", line),
+            Problem::BadName(name) => write!(f, "{:?} isn't a usable name, ignoring it", name),
+            Problem::CdAboveRoot => write!(f, "cd .. from /, staying put"),
+            Problem::CdIntoFile(path) => write!(f, "cd into {}, which is a file", path),
+            Problem::SizeChanged { path, old, new } => {
+                write!(f, "{} changed size from {} to {}", path, old, new)
+            }
+            Problem::KindChanged { path, now_dir } => {
+                let (old, new) = if *now_dir {
+                    ("file", "directory")
+                } else {
+                    ("directory", "file")
+                };
+                write!(f, "{} changed from a {} to a {}", path, old, new)
+            }
+            Problem::Vanished(path) => write!(f, "{} isn't there any more, removing it", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub line: usize,
+    pub problem: Problem,
+}
+
+impl Warning {
+    // What [Day7::parse] complains about, since it doesn't put up with any of this.
+    pub fn to_parse_error(&self) -> ParseError {
+        let (text, expected) = match &self.problem {
+            Problem::UnknownCommand(line) => (line.clone(), "a cd or ls command".to_string()),
+            Problem::OutputOutsideLs(line) => (line.clone(), "a command".to_string()),
+            Problem::BadListing(line) => (line.clone(), "dir <name> or <size> <name>".to_string()),
+            Problem::BadName(name) => (name.clone(), "a file or directory name".to_string()),
+            Problem::CdAboveRoot => ("cd ..".to_string(), "a directory to go up to".to_string()),
+            Problem::CdIntoFile(path) => (path.clone(), "a directory".to_string()),
+            Problem::SizeChanged { path, old, new } => (
+                new.to_string(),
+                format!("{} to still have size {}", path, old),
+            ),
+            Problem::KindChanged { path, now_dir } => {
+                let now = if *now_dir { "a directory" } else { "a file" };
+                (now.to_string(), format!("{} not to change type", path))
+            }
+            Problem::Vanished(path) => (String::new(), format!("{} to be listed again", path)),
+        };
+        ParseError::new(text, expected).at_line(self.line)
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.problem)
+    }
+}
+
+// Everything that happened while parsing, apart from the filesystem itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    pub warnings: Vec<Warning>,
+    pub lines: usize,
+    pub listings: usize,
+    // Listings of a directory that had already been listed.
+    pub relistings: usize,
+    // Directories we know exist, but never saw the contents of, so their sizes (and everything
+    // above them) might be too small.
+    pub unlisted: Vec<String>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Read {} lines, with {} listings ({} of directories already listed) and {} warnings.",
+            self.lines,
+            self.listings,
+            self.relistings,
+            self.warnings.len()
+        )?;
+        if self.unlisted.is_empty() {
+            write!(
+                f,
+                "Every directory was listed, so all the sizes are complete."
+            )
+        } else {
+            write!(
+                f,
+                "{} directories were never listed, so sizes might be too small: {}",
+                self.unlisted.len(),
+                self.unlisted.join(", ")
+            )
+        }
+    }
+}
+
+// The ls we're in the middle of reading the output of.
+struct Listing {
+    dir: NodeId,
+    line: usize,
+    seen: HashSet<NodeId>,
+}
+
+struct Parser {
+    filesystem: Filesystem,
+    report: Report,
+    working_dir: NodeId,
+    listing: Option<Listing>,
+    listed: HashSet<NodeId>,
+}
+
+impl Parser {
+    fn warn(&mut self, line: usize, problem: Problem) {
+        self.report.warnings.push(Warning { line, problem });
+    }
+
+    // Anything in the directory that didn't show up this time has gone.
+    fn finish_listing(&mut self) {
+        let Some(listing) = self.listing.take() else {
+            return;
+        };
+        let vanished: Vec<NodeId> = self
+            .filesystem
+            .children(listing.dir)
+            .iter()
+            .copied()
+            .filter(|child| !listing.seen.contains(child))
+            .collect();
+        for child in vanished {
+            self.warn(listing.line, Problem::Vanished(self.filesystem.path(child)));
+            self.filesystem.remove(child);
+        }
+    }
+
+    fn cd(&mut self, line: usize, name: &str) {
+        self.working_dir = match name {
+            "/" => self.filesystem.root(),
+            ".." => match self.filesystem.parent_dir(self.working_dir) {
+                Some(parent) => parent,
+                None => {
+                    self.warn(line, Problem::CdAboveRoot);
+                    self.working_dir
+                }
+            },
+            _ if !is_valid_name(name) => {
+                self.warn(line, Problem::BadName(name.to_string()));
+                self.working_dir
+            }
+            _ => match self.filesystem.child(self.working_dir, name) {
+                Some(file) if !self.filesystem.is_dir(file) => {
+                    self.warn(line, Problem::CdIntoFile(self.filesystem.path(file)));
+                    self.working_dir
+                }
+                _ => self.filesystem.add_subdir(self.working_dir, name),
+            },
+        }
+    }
+
+    fn ls(&mut self, line: usize) {
+        if !self.listed.insert(self.working_dir) {
+            self.report.relistings += 1;
+        }
+        self.report.listings += 1;
+        self.listing = Some(Listing {
+            dir: self.working_dir,
+            line,
+            seen: HashSet::new(),
+        });
+    }
+
+    fn listed_entry(&mut self, line: usize, text: &str) {
+        let Some(listing) = &self.listing else {
+            self.warn(line, Problem::OutputOutsideLs(text.to_string()));
+            return;
+        };
+        let dir = listing.dir;
+
+        let (file_size, name) = match text.strip_prefix("dir ") {
+            Some(name) => (None, name),
+            None => {
+                let mut tokens = text.split(' ');
+                let size = tokens.next().and_then(|size| size.parse().ok());
+                match (size, tokens.next(), tokens.next()) {
+                    (Some(size), Some(name), None) => (Some(size), name),
+                    _ => {
+                        self.warn(line, Problem::BadListing(text.to_string()));
+                        return;
+                    }
+                }
+            }
+        };
+        if !is_valid_name(name) {
+            self.warn(line, Problem::BadName(name.to_string()));
+            return;
+        }
+
+        let existing = self.filesystem.child(dir, name);
+        if let Some(existing) = existing {
+            let was_dir = self.filesystem.is_dir(existing);
+            if was_dir != file_size.is_none() {
+                let path = self.filesystem.path(existing);
+                self.warn(
+                    line,
+                    Problem::KindChanged {
+                        path,
+                        now_dir: !was_dir,
+                    },
+                );
+                self.filesystem.remove(existing);
+            }
+        }
+
+        let id = match file_size {
+            None => self.filesystem.add_subdir(dir, name),
+            Some(size) => match self.filesystem.child(dir, name) {
+                Some(file) if self.filesystem.file_size(file) != Some(size) => {
+                    let old = self.filesystem.size(file);
+                    let path = self.filesystem.path(file);
+                    self.warn(
+                        line,
+                        Problem::SizeChanged {
+                            path,
+                            old,
+                            new: size,
+                        },
+                    );
+                    self.filesystem.set_file_size(file, size);
+                    file
+                }
+                _ => self.filesystem.add_file(dir, name, size),
+            },
+        };
+        if let Some(listing) = &mut self.listing {
+            listing.seen.insert(id);
+        }
+    }
+}
+
+// Never fails, but see [Report::warnings] for anything that looked wrong.
+pub fn parse_transcript(s: &str) -> (Filesystem, Report) {
+    let filesystem = Filesystem::new();
+    let mut parser = Parser {
+        working_dir: filesystem.root(),
+        filesystem,
+        report: Report::default(),
+        listing: None,
+        listed: HashSet::new(),
+    };
+
+    for (line, text) in numbered_lines(s) {
+        parser.report.lines += 1;
+        let Some(command) = text.strip_prefix("$ ") else {
+            parser.listed_entry(line, text);
+            continue;
+        };
+        parser.finish_listing();
+        if command == "ls" {
+            parser.ls(line);
+        } else if let Some(name) = command.strip_prefix("cd ") {
+            parser.cd(line, name);
+        } else {
+            parser.warn(line, Problem::UnknownCommand(text.to_string()));
+        }
+    }
+    parser.finish_listing();
+
+    let Parser {
+        filesystem,
+        mut report,
+        listed,
+        ..
+    } = parser;
+    // Vanished entries only get noticed at the end of a listing, so put them back in order.
+    report.warnings.sort_by_key(|warning| warning.line);
+    report.unlisted = filesystem
+        .dirs()
+        .filter(|dir| !listed.contains(dir))
+        .map(|dir| filesystem.path(dir))
+        .collect();
+    (filesystem, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day7;
+    use aoc_common::Solution;
+
+    fn problems(report: &Report) -> Vec<String> {
+        report.warnings.iter().map(Warning::to_string).collect()
+    }
+
+    #[test]
+    fn example_is_clean() {
+        let (filesystem, report) = parse_transcript(Day7::EXAMPLE);
+        assert!(report.warnings.is_empty());
+        assert_eq!(report.lines, 23);
+        assert_eq!(report.listings, 4);
+        assert_eq!(report.relistings, 0);
+        assert!(report.unlisted.is_empty());
+        assert_eq!(filesystem.size(filesystem.root()), 48381165);
+    }
+
+    #[test]
+    fn messy_transcript() {
+        let transcript = "\
+$ cd /
+$ ls
+dir a
+100 b
+7 c
+$ cd a
+$ cd deeper
+$ ls
+5 x
+$ cd ..
+$ cd ..
+$ cd ..
+$ pwd
+/
+oops
+$ ls
+dir a
+150 b
+dir c
+what is this
+$ cd b
+$ cd a
+$ cd new
+";
+        let (filesystem, report) = parse_transcript(transcript);
+        assert_eq!(
+            problems(&report),
+            vec![
+                "line 12: cd .. from /, staying put",
+                "line 13: unknown command \"$ pwd\", ignoring it",
+                "line 14: \"/\" isn't from an ls, ignoring it",
+                "line 15: \"oops\" isn't from an ls, ignoring it",
+                "line 18: /b changed size from 100 to 150",
+                "line 19: /c changed from a file to a directory",
+                "line 20: couldn't understand \"what is this\", ignoring it",
+                "line 21: cd into /b, which is a file",
+            ]
+        );
+        assert_eq!(filesystem.size(filesystem.root()), 155);
+        assert_eq!(
+            filesystem.lookup("/a/new").map(|id| filesystem.is_dir(id)),
+            Some(true)
+        );
+        assert_eq!(report.listings, 3);
+        assert_eq!(report.relistings, 1);
+        assert_eq!(report.unlisted, vec!["/a", "/a/new", "/c"]);
+        assert_eq!(
+            report.to_string(),
+            "Read 23 lines, with 3 listings (1 of directories already listed) and 8 warnings.\n\
+             3 directories were never listed, so sizes might be too small: /a, /a/new, /c"
+        );
+    }
+
+    #[test]
+    fn relisting_removes_vanished_entries() {
+        let transcript = "\
+$ cd /
+$ ls
+dir a
+1 b
+2 c
+$ cd a
+$ ls
+10 d
+$ cd ..
+$ ls
+2 c
+";
+        let (filesystem, report) = parse_transcript(transcript);
+        assert_eq!(
+            problems(&report),
+            vec![
+                "line 10: /a isn't there any more, removing it",
+                "line 10: /b isn't there any more, removing it",
+            ]
+        );
+        assert_eq!(filesystem.size(filesystem.root()), 2);
+        assert_eq!(filesystem.lookup("/a"), None);
+        assert!(report.unlisted.is_empty());
+
+        // Day7::parse doesn't put up with any of this.
+        assert_eq!(
+            Day7::parse(transcript).unwrap_err().to_string(),
+            "line 10: expected /a to be listed again, found nothing"
+        );
+    }
+
+    #[test]
+    fn bad_names() {
+        let transcript = "\
+$ cd /
+$ ls
+dir .
+dir a/b
+3 ..
+4 c
+$ cd .
+$ cd a/b
+$ cd ..
+";
+        let (filesystem, report) = parse_transcript(transcript);
+        assert_eq!(
+            problems(&report),
+            vec![
+                "line 3: \".\" isn't a usable name, ignoring it",
+                "line 4: \"a/b\" isn't a usable name, ignoring it",
+                "line 5: \"..\" isn't a usable name, ignoring it",
+                "line 7: \".\" isn't a usable name, ignoring it",
+                "line 8: \"a/b\" isn't a usable name, ignoring it",
+                "line 9: cd .. from /, staying put",
+            ]
+        );
+        let root = filesystem.root();
+        assert_eq!(
+            filesystem.children(root),
+            &[filesystem.lookup("/c").unwrap()]
+        );
+        assert_eq!(filesystem.size(root), 4);
+
+        assert_eq!(
+            Day7::parse(transcript).unwrap_err().to_string(),
+            "line 3: expected a file or directory name, found \".\""
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use std::fmt::Write;
+
+use crate::filesystem::{is_valid_name, Filesystem, NodeId};
+
+// A little shell for poking around a [Filesystem], e.g. one rebuilt from a transcript. Each
+// command gives back what it would print, or an error message.
+
+pub const HELP: &str = "\
+Commands:
+  cd [<path>]            change directory (to / if there's no path)
+  ls [<path>]            list a directory, in the same format as the puzzle input
+  pwd                    print the current directory
+  du [<path>]            print the total size of every directory
+  find [<path>] [-type f|d] [-size [+|-]<n>]
+                         print everything bigger than (+), smaller than (-), or exactly <n>
+  tree [<path>]          draw everything under a directory
+  mkdir <path>           make a directory
+  touch <size> <path>    make a file, or change its size
+  rm [-r] <path>         remove a file, or a directory with -r
+  mv <from> <to>         move or rename something
+  help                   print this
+  exit                   leave";
+
+pub struct Shell {
+    filesystem: Filesystem,
+    cwd: NodeId,
+}
+
+// The test for find's -size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeTest {
+    Bigger(usize),
+    Smaller(usize),
+    Exactly(usize),
+}
+
+impl SizeTest {
+    fn parse(s: &str) -> Result<Self, String> {
+        let (test, n): (fn(usize) -> SizeTest, &str) = match s.chars().next() {
+            Some('+') => (SizeTest::Bigger, &s[1..]),
+            Some('-') => (SizeTest::Smaller, &s[1..]),
+            _ => (SizeTest::Exactly, s),
+        };
+        n.parse()
+            .map(test)
+            .map_err(|_| format!("find: bad size {}", s))
+    }
+
+    fn matches(&self, size: usize) -> bool {
+        match self {
+            SizeTest::Bigger(n) => size > *n,
+            SizeTest::Smaller(n) => size < *n,
+            SizeTest::Exactly(n) => size == *n,
+        }
+    }
+}
+
+impl Shell {
+    pub fn new(filesystem: Filesystem) -> Self {
+        let cwd = filesystem.root();
+        Shell { filesystem, cwd }
+    }
+
+    pub fn filesystem(&self) -> &Filesystem {
+        &self.filesystem
+    }
+
+    pub fn prompt(&self) -> String {
+        format!("{}$ ", self.filesystem.path(self.cwd))
+    }
+
+    pub fn run(&mut self, line: &str) -> Result<String, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words[..] {
+            [] => Ok(String::new()),
+            ["cd"] => {
+                self.cwd = self.filesystem.root();
+                Ok(String::new())
+            }
+            ["cd", path] => {
+                self.cwd = self.lookup_dir(path)?;
+                Ok(String::new())
+            }
+            ["ls"] => self.ls("."),
+            ["ls", path] => self.ls(path),
+            ["pwd"] => Ok(format!("{}\n", self.filesystem.path(self.cwd))),
+            ["du"] => self.du("."),
+            ["du", path] => self.du(path),
+            ["find", ref args @ ..] => self.find(args),
+            ["tree"] => self.tree("."),
+            ["tree", path] => self.tree(path),
+            ["mkdir", path] => self.mkdir(path),
+            ["touch", size, path] => self.touch(size, path),
+            ["rm", path] => self.rm(path, false),
+            ["rm", "-r", path] => self.rm(path, true),
+            ["mv", from, to] => self.mv(from, to),
+            ["help"] => Ok(format!("{}\n", HELP)),
+            [command, ..] => {
+                let known = [
+                    "cd", "ls", "pwd", "du", "tree", "mkdir", "touch", "rm", "mv", "help",
+                ];
+                if known.contains(&command) {
+                    Err(format!("{}: wrong arguments (try help)", command))
+                } else {
+                    Err(format!("{}: unknown command (try help)", command))
+                }
+            }
+        }
+    }
+
+    fn lookup(&self, path: &str) -> Result<NodeId, String> {
+        self.filesystem
+            .resolve(self.cwd, path)
+            .ok_or_else(|| format!("{}: no such file or directory", path))
+    }
+
+    fn lookup_dir(&self, path: &str) -> Result<NodeId, String> {
+        let id = self.lookup(path)?;
+        if self.filesystem.is_dir(id) {
+            Ok(id)
+        } else {
+            Err(format!("{}: not a directory", path))
+        }
+    }
+
+    // For making something new at [path]: the directory it goes in, and its name.
+    fn parent_and_name<'a>(&self, original: &'a str) -> Result<(NodeId, &'a str), String> {
+        // "mkdir foo/" makes foo, as usual.
+        let path = original.trim_end_matches('/');
+        let (dir, name) = match path.rsplit_once('/') {
+            None => (self.cwd, path),
+            Some(("", name)) => (self.filesystem.root(), name),
+            Some((dir, name)) => (self.lookup_dir(dir)?, name),
+        };
+        if !is_valid_name(name) {
+            return Err(format!("{}: not a valid name", original));
+        }
+        Ok((dir, name))
+    }
+
+    fn sorted_children(&self, dir: NodeId) -> Vec<NodeId> {
+        let mut children = self.filesystem.children(dir).to_vec();
+        children.sort_by_key(|child| self.filesystem.name(*child));
+        children
+    }
+
+    // Everything under [id] (including itself), in the order [find] prints them.
+    fn walk(&self, id: NodeId) -> Vec<NodeId> {
+        let mut result = vec![];
+        let mut stack = vec![id];
+        while let Some(current) = stack.pop() {
+            result.push(current);
+            stack.extend(self.sorted_children(current).into_iter().rev());
+        }
+        result
+    }
+
+    fn ls(&self, path: &str) -> Result<String, String> {
+        let dir = self.lookup_dir(path)?;
+        let mut output = String::new();
+        for child in self.sorted_children(dir) {
+            let name = self.filesystem.name(child);
+            match self.filesystem.file_size(child) {
+                None => writeln!(output, "dir {}", name),
+                Some(size) => writeln!(output, "{} {}", size, name),
+            }
+            .expect("BUG: writing to a String failed");
+        }
+        Ok(output)
+    }
+
+    // Like the real du, subdirectories come before the directories they're in.
+    fn du(&self, path: &str) -> Result<String, String> {
+        fn add_lines(shell: &Shell, dir: NodeId, output: &mut String) {
+            for child in shell.sorted_children(dir) {
+                if shell.filesystem.is_dir(child) {
+                    add_lines(shell, child, output);
+                }
+            }
+            writeln!(
+                output,
+                "{}\t{}",
+                shell.filesystem.size(dir),
+                shell.filesystem.path(dir)
+            )
+            .expect("BUG: writing to a String failed");
+        }
+
+        let dir = self.lookup_dir(path)?;
+        let mut output = String::new();
+        add_lines(self, dir, &mut output);
+        Ok(output)
+    }
+
+    fn find(&self, args: &[&str]) -> Result<String, String> {
+        let (path, mut options) = match args {
+            [path, rest @ ..] if !path.starts_with('-') => (*path, rest),
+            _ => (".", args),
+        };
+
+        let mut want_dirs = None;
+        let mut size_test = None;
+        while !options.is_empty() {
+            match options {
+                ["-type", "f", rest @ ..] => (want_dirs, options) = (Some(false), rest),
+                ["-type", "d", rest @ ..] => (want_dirs, options) = (Some(true), rest),
+                ["-size", size, rest @ ..] => {
+                    (size_test, options) = (Some(SizeTest::parse(size)?), rest)
+                }
+                _ => return Err(format!("find: didn't understand {}", options.join(" "))),
+            }
+        }
+
+        let mut output = String::new();
+        for id in self.walk(self.lookup(path)?) {
+            let wanted_type = want_dirs.is_none_or(|dirs| dirs == self.filesystem.is_dir(id));
+            let wanted_size = size_test.is_none_or(|test| test.matches(self.filesystem.size(id)));
+            if wanted_type && wanted_size {
+                writeln!(output, "{}", self.filesystem.path(id))
+                    .expect("BUG: writing to a String failed");
+            }
+        }
+        Ok(output)
+    }
+
+    // The same format as the example in the puzzle, but with directory sizes too.
+    fn tree(&self, path: &str) -> Result<String, String> {
+        let start = self.lookup(path)?;
+        let mut output = String::new();
+        let mut stack = vec![(start, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let name = match self.filesystem.parent_dir(id) {
+                None => "/",
+                Some(_) => self.filesystem.name(id),
+            };
+            let kind = if self.filesystem.is_dir(id) {
+                "dir"
+            } else {
+                "file"
+            };
+            writeln!(
+                output,
+                "{}- {} ({}, size={})",
+                "  ".repeat(depth),
+                name,
+                kind,
+                self.filesystem.size(id)
+            )
+            .expect("BUG: writing to a String failed");
+            for child in self.sorted_children(id).into_iter().rev() {
+                stack.push((child, depth + 1));
+            }
+        }
+        Ok(output)
+    }
+
+    fn mkdir(&mut self, path: &str) -> Result<String, String> {
+        let (dir, name) = self.parent_and_name(path)?;
+        if self.filesystem.child(dir, name).is_some() {
+            return Err(format!("{}: already exists", path));
+        }
+        self.filesystem.add_subdir(dir, name);
+        Ok(String::new())
+    }
+
+    fn touch(&mut self, size: &str, path: &str) -> Result<String, String> {
+        let size = size
+            .parse()
+            .map_err(|_| format!("touch: bad size {}", size))?;
+        let (dir, name) = self.parent_and_name(path)?;
+        match self.filesystem.child(dir, name) {
+            None => {
+                self.filesystem.add_file(dir, name, size);
+            }
+            Some(file) if !self.filesystem.is_dir(file) => {
+                self.filesystem.set_file_size(file, size)
+            }
+            Some(_) => return Err(format!("{}: is a directory", path)),
+        }
+        Ok(String::new())
+    }
+
+    fn rm(&mut self, path: &str, recursive: bool) -> Result<String, String> {
+        let id = self.lookup(path)?;
+        if self.filesystem.is_dir(id) && !recursive {
+            return Err(format!("{}: is a directory (use rm -r)", path));
+        }
+        if self.filesystem.is_inside(self.cwd, id) {
+            return Err(format!("{}: can't remove the current directory", path));
+        }
+        self.filesystem.remove(id);
+        Ok(String::new())
+    }
+
+    // Moving onto an existing directory puts it inside that directory, like the real mv.
+    fn mv(&mut self, from: &str, to: &str) -> Result<String, String> {
+        let id = self.lookup(from)?;
+        if id == self.filesystem.root() {
+            return Err("mv: can't move /".to_string());
+        }
+
+        let (dir, name) = match self.filesystem.resolve(self.cwd, to) {
+            Some(dir) if self.filesystem.is_dir(dir) => (dir, self.filesystem.name(id)),
+            _ => self.parent_and_name(to)?,
+        };
+        let name = name.to_string();
+        if self.filesystem.is_inside(dir, id) {
+            return Err(format!("mv: can't move {} inside itself", from));
+        }
+        if self
+            .filesystem
+            .child(dir, &name)
+            .is_some_and(|existing| existing != id)
+        {
+            return Err(format!("{}: already exists", to));
+        }
+        self.filesystem.move_node(id, dir, &name);
+        Ok(String::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day7;
+    use aoc_common::Solution;
+
+    fn shell() -> Shell {
+        Shell::new(Day7::parse_example().unwrap())
+    }
+
+    #[test]
+    fn look_around() {
+        let mut shell = shell();
+        assert_eq!(
+            shell.run("ls").unwrap(),
+            "dir a\n14848514 b.txt\n8504156 c.dat\ndir d\n"
+        );
+        assert_eq!(shell.run("cd a/e").unwrap(), "");
+        assert_eq!(shell.run("pwd").unwrap(), "/a/e\n");
+        assert_eq!(shell.prompt(), "/a/e$ ");
+        assert_eq!(shell.run("ls ../..").unwrap(), shell.run("ls /").unwrap());
+        shell.run("cd").unwrap();
+        assert_eq!(shell.run("pwd").unwrap(), "/\n");
+
+        assert_eq!(
+            shell.run("du").unwrap(),
+            "584\t/a/e\n94853\t/a\n24933642\t/d\n48381165\t/\n"
+        );
+        assert_eq!(
+            shell.run("tree a").unwrap(),
+            "\
+- a (dir, size=94853)
+  - e (dir, size=584)
+    - i (file, size=584)
+  - f (file, size=29116)
+  - g (file, size=2557)
+  - h.lst (file, size=62596)
+"
+        );
+
+        // Part 1 is the total size of these.
+        assert_eq!(
+            shell.run("find -type d -size -100001").unwrap(),
+            "/a\n/a/e\n"
+        );
+        assert_eq!(
+            shell.run("find /d -size +5000000").unwrap(),
+            "/d\n/d/d.ext\n/d/d.log\n/d/k\n"
+        );
+        assert_eq!(shell.run("find a -size 584").unwrap(), "/a/e\n/a/e/i\n");
+    }
+
+    #[test]
+    fn make_changes() {
+        let mut shell = shell();
+        shell.run("mkdir /a/new").unwrap();
+        shell.run("touch 100 a/new/file").unwrap();
+        assert_eq!(
+            shell.run("du a").unwrap(),
+            "584\t/a/e\n100\t/a/new\n94953\t/a\n"
+        );
+
+        shell.run("touch 50 a/new/file").unwrap();
+        shell.run("mv a/new d").unwrap();
+        shell.run("mv /b.txt d/new/renamed.txt").unwrap();
+        assert_eq!(
+            shell.run("du").unwrap(),
+            "584\t/a/e\n94853\t/a\n14848564\t/d/new\n39782206\t/d\n48381215\t/\n"
+        );
+
+        shell.run("rm -r d").unwrap();
+        shell.run("rm c.dat").unwrap();
+        assert_eq!(shell.run("du").unwrap(), "584\t/a/e\n94853\t/a\n94853\t/\n");
+    }
+
+    #[test]
+    fn errors() {
+        let mut shell = shell();
+        let error = |shell: &mut Shell, command| shell.run(command).unwrap_err();
+        assert_eq!(error(&mut shell, "cd x"), "x: no such file or directory");
+        assert_eq!(error(&mut shell, "cd b.txt"), "b.txt: not a directory");
+        assert_eq!(error(&mut shell, "mkdir a"), "a: already exists");
+        assert_eq!(error(&mut shell, "rm a"), "a: is a directory (use rm -r)");
+        assert_eq!(
+            error(&mut shell, "mv a a/e"),
+            "mv: can't move a inside itself"
+        );
+        assert_eq!(error(&mut shell, "mv b.txt c.dat"), "c.dat: already exists");
+        assert_eq!(error(&mut shell, "touch big a/x"), "touch: bad size big");
+        assert_eq!(error(&mut shell, "touch 5 a"), "a: is a directory");
+        assert_eq!(
+            error(&mut shell, "ls a b"),
+            "ls: wrong arguments (try help)"
+        );
+        assert_eq!(
+            error(&mut shell, "frobnicate"),
+            "frobnicate: unknown command (try help)"
+        );
+
+        // Trailing slashes aren't part of the name.
+        assert_eq!(error(&mut shell, "mkdir /"), "/: not a valid name");
+        assert_eq!(error(&mut shell, "mkdir a/.."), "a/..: not a valid name");
+        assert_eq!(error(&mut shell, "mkdir a/"), "a/: already exists");
+        shell.run("mkdir foo/").unwrap();
+        shell.run("touch 5 foo/bar/").unwrap();
+        shell.run("cd foo").unwrap();
+        assert_eq!(shell.run("ls").unwrap(), "5 bar\n");
+        shell.run("cd /").unwrap();
+
+        shell.run("cd a/e").unwrap();
+        assert_eq!(
+            error(&mut shell, "rm -r /a"),
+            "/a: can't remove the current directory"
+        );
+    }
+}
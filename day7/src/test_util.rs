@@ -0,0 +1,21 @@
+// Helpers shared between the tests in different modules.
+
+use crate::filesystem::Filesystem;
+
+// Every path and its size, sorted, for checking two filesystems match without caring about the
+// order things were added in.
+pub(crate) fn sizes(filesystem: &Filesystem) -> Vec<(String, usize, bool)> {
+    let mut sizes: Vec<_> = filesystem
+        .descendants(filesystem.root())
+        .into_iter()
+        .map(|id| {
+            (
+                filesystem.path(id),
+                filesystem.size(id),
+                filesystem.is_dir(id),
+            )
+        })
+        .collect();
+    sizes.sort();
+    sizes
+}
@@ -0,0 +1,387 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::filesystem::{is_valid_name, Filesystem, NodeId};
+
+// Going the other way from the parser: write out a terminal session that rebuilds a given
+// [Filesystem], by cd-ing into every directory and listing it.
+//
+// Directories always get listed after their parent, whatever the order. That's not something the
+// parser needs, but it's what a person at a terminal would do.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    DepthFirst,
+    BreadthFirst,
+    // Any directory whose parent has been listed can come next. This also shuffles the output of
+    // each ls.
+    Random,
+}
+
+impl FromStr for Order {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dfs" => Ok(Order::DepthFirst),
+            "bfs" => Ok(Order::BreadthFirst),
+            "random" => Ok(Order::Random),
+            _ => Err(format!("Unknown order {}, expected dfs, bfs or random", s)),
+        }
+    }
+}
+
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Order::DepthFirst => write!(f, "dfs"),
+            Order::BreadthFirst => write!(f, "bfs"),
+            Order::Random => write!(f, "random"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    pub order: Order,
+    // List directories again whenever we pass back through them on the way somewhere else.
+    pub relist: bool,
+    // Only used for [Order::Random].
+    pub seed: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            order: Order::DepthFirst,
+            relist: false,
+            seed: 0,
+        }
+    }
+}
+
+// A little LCG, which is plenty random enough for shuffling directories about, and means the same
+// seed always gives the same transcript. The tests use it for made-up filesystems too.
+pub(crate) struct Lcg(pub(crate) u64);
+
+impl Lcg {
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % n as u64) as usize
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// The parser splits lines on spaces, and won't take names that can't be part of a path, so names
+// like those can't be written down.
+fn check_name(filesystem: &Filesystem, id: NodeId) -> Result<(), String> {
+    let name = filesystem.name(id);
+    if !is_valid_name(name) || name.contains(['\n', ' ']) {
+        Err(format!(
+            "Can't write {:?} in a transcript",
+            filesystem.path(id)
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+// The directories in the order we'll visit them.
+fn visit_order(filesystem: &Filesystem, order: Order, rng: &mut Lcg) -> Vec<NodeId> {
+    let subdirs = |dir: NodeId| {
+        filesystem
+            .children(dir)
+            .iter()
+            .copied()
+            .filter(|child| filesystem.is_dir(*child))
+    };
+    match order {
+        Order::DepthFirst => filesystem.dirs().collect(),
+        Order::BreadthFirst => {
+            let mut visits = vec![];
+            let mut queue = VecDeque::from([filesystem.root()]);
+            while let Some(dir) = queue.pop_front() {
+                visits.push(dir);
+                queue.extend(subdirs(dir));
+            }
+            visits
+        }
+        Order::Random => {
+            let mut visits = vec![];
+            let mut frontier = vec![filesystem.root()];
+            while !frontier.is_empty() {
+                let dir = frontier.swap_remove(rng.below(frontier.len()));
+                visits.push(dir);
+                frontier.extend(subdirs(dir));
+            }
+            visits
+        }
+    }
+}
+
+// The directories from the root down to [dir], including both.
+fn chain(filesystem: &Filesystem, dir: NodeId) -> Vec<NodeId> {
+    let mut chain = vec![dir];
+    while let Some(parent) = filesystem.parent_dir(*chain.last().unwrap()) {
+        chain.push(parent);
+    }
+    chain.reverse();
+    chain
+}
+
+struct Writer<'a> {
+    filesystem: &'a Filesystem,
+    options: Options,
+    rng: Lcg,
+    lines: Vec<String>,
+    listed: HashSet<NodeId>,
+}
+
+impl Writer<'_> {
+    fn ls(&mut self, dir: NodeId) {
+        self.lines.push("$ ls".to_string());
+        let mut children = self.filesystem.children(dir).to_vec();
+        if self.options.order == Order::Random {
+            self.rng.shuffle(&mut children);
+        }
+        for child in children {
+            let name = self.filesystem.name(child);
+            self.lines.push(match self.filesystem.file_size(child) {
+                Some(size) => format!("{} {}", size, name),
+                None => format!("dir {}", name),
+            });
+        }
+        self.listed.insert(dir);
+    }
+
+    // We've just arrived at [dir] on the way somewhere else.
+    fn pass_through(&mut self, dir: NodeId) {
+        if self.options.relist && self.listed.contains(&dir) {
+            self.ls(dir);
+        }
+    }
+
+    // Get from [from] to [to], going up to wherever the paths split and then down again. If
+    // that's all the way up to the root, it's shorter to just cd / straight there.
+    fn cd(&mut self, from: NodeId, to: NodeId) {
+        let (from_chain, to_chain) = (chain(self.filesystem, from), chain(self.filesystem, to));
+        let common = from_chain
+            .iter()
+            .zip(&to_chain)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let ups = from_chain.len() - common;
+
+        if common == 1 && ups > 1 {
+            self.lines.push("$ cd /".to_string());
+            self.pass_through(self.filesystem.root());
+        } else {
+            for dir in from_chain[common - 1..from_chain.len() - 1].iter().rev() {
+                self.lines.push("$ cd ..".to_string());
+                self.pass_through(*dir);
+            }
+        }
+        for dir in &to_chain[common..] {
+            self.lines
+                .push(format!("$ cd {}", self.filesystem.name(*dir)));
+            if *dir != to {
+                self.pass_through(*dir);
+            }
+        }
+    }
+}
+
+// A transcript that the parser turns back into [filesystem]. It starts with "$ cd /" like the
+// puzzle input does, and ends with a newline.
+pub fn write_transcript(filesystem: &Filesystem, options: Options) -> Result<String, String> {
+    for id in filesystem
+        .descendants(filesystem.root())
+        .into_iter()
+        .skip(1)
+    {
+        check_name(filesystem, id)?;
+    }
+
+    let mut writer = Writer {
+        filesystem,
+        options,
+        rng: Lcg(options.seed),
+        lines: vec!["$ cd /".to_string()],
+        listed: HashSet::new(),
+    };
+    let visits = visit_order(filesystem, options.order, &mut writer.rng);
+    let mut current = filesystem.root();
+    for dir in visits {
+        writer.cd(current, dir);
+        writer.ls(dir);
+        current = dir;
+    }
+
+    let mut transcript = writer.lines.join("\n");
+    transcript.push('\n');
+    Ok(transcript)
+}
+
+// A made-up filesystem with [nodes] files and directories in it, with names and sizes that look
+// like the puzzle's, for testing things on inputs bigger than ours.
+pub fn random_filesystem(nodes: usize, seed: u64) -> Filesystem {
+    const EXTENSIONS: [&str; 5] = ["", "", ".txt", ".dat", ".log"];
+    let mut rng = Lcg(seed);
+    let mut filesystem = Filesystem::new();
+    let mut dirs = vec![filesystem.root()];
+    for i in 0..nodes {
+        let dir = dirs[rng.below(dirs.len())];
+        let letters = 1 + rng.below(8);
+        let mut name: String = (0..letters)
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect();
+        let is_dir = rng.below(4) == 0;
+        if !is_dir {
+            name.push_str(EXTENSIONS[rng.below(EXTENSIONS.len())]);
+        }
+        // Make sure it's new, otherwise we'd add fewer nodes than we were asked for.
+        if filesystem.child(dir, &name).is_some() {
+            name.push_str(&i.to_string());
+        }
+
+        if is_dir {
+            dirs.push(filesystem.add_subdir(dir, &name));
+        } else {
+            filesystem.add_file(dir, &name, 1 + rng.below(300_000));
+        }
+    }
+    filesystem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::to_json;
+    use crate::test_util::sizes;
+    use crate::Day7;
+    use aoc_common::Solution;
+
+    #[test]
+    fn example() {
+        let filesystem = Day7::parse_example().unwrap();
+        let transcript = write_transcript(&filesystem, Options::default()).unwrap();
+        // This is the example, apart from going back up to / with one cd, rather than two.
+        assert_eq!(
+            transcript,
+            "\
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd /
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+"
+        );
+
+        let bfs = Options {
+            order: Order::BreadthFirst,
+            relist: true,
+            ..Options::default()
+        };
+        let transcript = write_transcript(&filesystem, bfs).unwrap();
+        let commands: Vec<&str> = transcript
+            .lines()
+            .filter(|line| line.starts_with('$'))
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                "$ cd /", "$ ls", "$ cd a", "$ ls", "$ cd ..", "$ ls", "$ cd d", "$ ls", "$ cd ..",
+                "$ ls", "$ cd a", "$ ls", "$ cd e", "$ ls"
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        for seed in 0..20 {
+            let filesystem = random_filesystem(200, seed);
+            for order in [Order::DepthFirst, Order::BreadthFirst, Order::Random] {
+                for relist in [false, true] {
+                    let options = Options {
+                        order,
+                        relist,
+                        seed,
+                    };
+                    let transcript = write_transcript(&filesystem, options).unwrap();
+                    let parsed = Day7::parse(&transcript).unwrap();
+                    if order == Order::Random {
+                        // The listings are shuffled, so things end up in a different order.
+                        assert_eq!(sizes(&parsed), sizes(&filesystem));
+                    } else {
+                        assert_eq!(to_json(&parsed), to_json(&filesystem));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_is_repeatable() {
+        let filesystem = random_filesystem(2000, 1);
+        assert_eq!(filesystem.descendants(filesystem.root()).len(), 2001);
+        let options = Options {
+            order: Order::Random,
+            relist: false,
+            seed: 7,
+        };
+        assert_eq!(
+            write_transcript(&filesystem, options),
+            write_transcript(&filesystem, options)
+        );
+        assert_ne!(
+            write_transcript(&filesystem, options),
+            write_transcript(&filesystem, Options { seed: 8, ..options })
+        );
+    }
+
+    #[test]
+    fn unwritable_names() {
+        let mut filesystem = Filesystem::new();
+        let root = filesystem.root();
+        let dir = filesystem.add_subdir(root, "a");
+        filesystem.add_file(dir, "two words", 1);
+        assert_eq!(
+            write_transcript(&filesystem, Options::default()),
+            Err("Can't write \"/a/two words\" in a transcript".to_string())
+        );
+
+        let mut filesystem = Filesystem::new();
+        let root = filesystem.root();
+        filesystem.add_file(root, ".", 1);
+        assert_eq!(
+            write_transcript(&filesystem, Options::default()),
+            Err("Can't write \"/.\" in a transcript".to_string())
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use aoc_common::grid::Grid;
+use aoc_common::point::Point2;
+use aoc_common::{Answer, ParseError, Solution};
+
+type Point = Point2<usize>;
+
+#[derive(Debug)]
+pub struct Trees {
+    grid: Grid<usize>,
+}
+
+impl Trees {
+    fn get(&self, p: &Point) -> usize {
+        *self
+            .grid
+            .get_point(*p)
+            .expect("BUG: [get] called with a point off the grid")
+    }
+
+    // The forest doesn't have to be square.
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.grid.positions().map(Point::from_grid_index).collect()
+    }
+}
+
+struct Line {
+    points: Vec<Point>,
+}
+
+impl Line {
+    fn all_for_grid(width: usize, height: usize) -> Vec<Line> {
+        let mut lines: Vec<Line> = Vec::new();
+
+        // Left-to-right and right-to-left
+        for y in 0..height {
+            let points: Vec<Point> = (0..width).map(|x| Point::new(x, y)).collect();
+
+            let mut points_rev = points.clone();
+            points_rev.reverse();
+
+            lines.push(Line { points });
+            lines.push(Line { points: points_rev });
+        }
+
+        // Top-to-bottom and bottom-to-top
+        for x in 0..width {
+            let points: Vec<Point> = (0..height).map(|y| Point::new(x, y)).collect();
+
+            let mut points_rev = points.clone();
+            points_rev.reverse();
+
+            lines.push(Line { points });
+            lines.push(Line { points: points_rev });
+        }
+
+        lines
+    }
+}
+
+fn trees_to_visible_dirs(t: &Trees) -> HashMap<Point, usize> {
+    let lines = Line::all_for_grid(t.width(), t.height());
+    let mut result = HashMap::new();
+
+    for p in t.points() {
+        result.insert(p, 0);
+    }
+
+    for line in lines {
+        // The first point in this direction is visible.
+        let first_point = &line.points[0];
+
+        // I'd love to define this as a closure / separate function instead - it's reused
+        // in the fold below.
+        // But if I do that, I seem to land in borrow-checker hell, or need to define a [fn]
+        // and pass in anything I want to capture explicitly, which is a bit gross.
+        result.insert(*first_point, result.get(first_point).unwrap() + 1);
+
+        let _ = &line.points[1..]
+            .iter()
+            .fold(t.get(first_point), |tallest_tree_so_far, point| {
+                let height_of_this_tree = t.get(point);
+                if height_of_this_tree > tallest_tree_so_far {
+                    result.insert(*point, result.get(point).unwrap() + 1);
+                }
+                height_of_this_tree.max(tallest_tree_so_far)
+            });
+    }
+    result
+}
+
+fn scenic_score(t: &Trees, p: &Point) -> usize {
+    fn scenic_score_along_line(t: &Trees, p: &Point, line: &Vec<Point>) -> usize {
+        let starting_size = t.get(p);
+        let mut count = 0;
+        for point in line {
+            count += 1;
+            let this_tree_size = t.get(point);
+            if this_tree_size >= starting_size {
+                return count;
+            }
+        }
+        count
+    }
+
+    let Point2 { x, y } = *p;
+
+    let to_right = (x + 1..t.width()).map(|x| Point::new(x, y)).collect();
+    let to_left = (0..x).rev().map(|x| Point::new(x, y)).collect();
+    let to_bottom = (y + 1..t.height()).map(|y| Point::new(x, y)).collect();
+    let to_up = (0..y).rev().map(|y| Point::new(x, y)).collect();
+    let lines = [to_right, to_left, to_bottom, to_up];
+
+    lines
+        .iter()
+        .map(|line| scenic_score_along_line(t, p, line))
+        .reduce(|x, y| x * y)
+        .unwrap()
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Trees;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        let grid = Grid::parse_with(s, |c| c.to_digit(10).map(|d| d as usize), "a digit")?;
+        Ok(Trees { grid })
+    }
+
+    fn part1(trees: &Trees) -> Answer {
+        let visible_count = trees_to_visible_dirs(trees);
+
+        let total_visible: usize = visible_count
+            .values()
+            .copied()
+            .filter(|c| *c > 0)
+            .fold(0, |x, _| x + 1);
+
+        total_visible.into()
+    }
+
+    fn part2(trees: &Trees) -> Answer {
+        let best_scenic_score = trees
+            .points()
+            .iter()
+            .map(|p| scenic_score(trees, p))
+            .max()
+            .unwrap();
+
+        best_scenic_score.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day8::parse_example().unwrap();
+        assert_eq!(Day8::part1(&input), Answer::Int(21));
+        assert_eq!(Day8::part2(&input), Answer::Int(8));
+    }
+
+    #[test]
+    fn scenic_scores_from_the_puzzle() {
+        let trees = Day8::parse_example().unwrap();
+        assert_eq!(scenic_score(&trees, &Point::new(2, 1)), 4);
+        assert_eq!(scenic_score(&trees, &Point::new(2, 3)), 8);
+        // Anything on the edge can't see anything in one direction.
+        assert_eq!(scenic_score(&trees, &Point::new(3, 0)), 0);
+    }
+
+    #[test]
+    fn rectangular_forests() {
+        // The first three rows of the example.
+        let wide = Day8::parse("30373\n25512\n65332\n").unwrap();
+        assert_eq!((wide.width(), wide.height()), (5, 3));
+        assert_eq!(Day8::part1(&wide), Answer::Int(14));
+        assert_eq!(Day8::part2(&wide), Answer::Int(2));
+        assert_eq!(scenic_score(&wide, &Point::new(2, 1)), 2);
+
+        // Turning it on its side shouldn't change anything.
+        let tall = Trees {
+            grid: wide.grid.transpose(),
+        };
+        assert_eq!((tall.width(), tall.height()), (3, 5));
+        assert_eq!(Day8::part1(&tall), Answer::Int(14));
+        assert_eq!(Day8::part2(&tall), Answer::Int(2));
+
+        let single_row = Day8::parse("12321").unwrap();
+        assert_eq!(Day8::part1(&single_row), Answer::Int(5));
+        assert_eq!(Day8::part2(&single_row), Answer::Int(0));
+    }
+
+    #[test]
+    fn ragged_rows() {
+        let error = Day8::parse("303\n2551\n653").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected 3 cells, like the first row, found \"4 cells\""
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+
+use aoc_common::input::{parse_lines_with, parse_number};
+use aoc_common::point::{Dir, Point2};
+use aoc_common::{Answer, ParseError, Solution};
+
+type Point = Point2<i64>;
+
+// Where the tail ends up after the knot in front of it has moved to [other].
+fn move_towards_point(tail: Point, other: &Point) -> Point {
+    // We get to move at most 1 towards the other, but in both the x/y axes.
+    let diff = *other - tail;
+
+    if tail.chebyshev_distance(other) <= 1 {
+        // We're touching, so nothing to do.
+        tail
+    } else {
+        // We're not touching. Move towards!
+        let cap_to_1 = |x: i64| x.clamp(-1, 1);
+        tail + Point::new(cap_to_1(diff.x), cap_to_1(diff.y))
+    }
+}
+
+// A line like "R 4"
+fn parse_move(s: &str) -> Result<(Dir, usize), ParseError> {
+    let (dir, amount) = s
+        .split_once(' ')
+        .ok_or_else(|| ParseError::at_column(1, s, "a direction and a distance"))?;
+
+    let dir = dir.parse()?;
+    let amount = parse_number(amount, s.len() - amount.len() + 1)?;
+    Ok((dir, amount))
+}
+
+// Simulate a rope of [knot_count] knots, returning the number of distinct positions visited by the tail.
+fn count_tail_positions(head_moves: &[(Dir, usize)], knot_count: usize) -> usize {
+    let mut knot_positions: Vec<Point> = vec![Point::new(0, 0); knot_count];
+    let mut all_tail_positions = HashSet::new();
+
+    for (dir, moves) in head_moves {
+        for _ in 0..*moves {
+            for i in 0..knot_positions.len() {
+                if i == 0 {
+                    // move the head
+                    knot_positions[0] += dir.offset();
+                } else {
+                    // move knot i towards i-1
+                    knot_positions[i] =
+                        move_towards_point(knot_positions[i], &knot_positions[i - 1]);
+                }
+            }
+            all_tail_positions.insert(knot_positions[knot_count - 1]);
+        }
+    }
+
+    all_tail_positions.len()
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+    const EXAMPLE: &'static str = include_str!("example");
+
+    type Input = Vec<(Dir, usize)>;
+
+    fn parse(s: &str) -> Result<Self::Input, ParseError> {
+        parse_lines_with(s, parse_move)
+    }
+
+    fn part1(head_moves: &Self::Input) -> Answer {
+        count_tail_positions(head_moves, 2).into()
+    }
+
+    fn part2(head_moves: &Self::Input) -> Answer {
+        count_tail_positions(head_moves, 10).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example() {
+        let input = Day9::parse_example().unwrap();
+        assert_eq!(Day9::part1(&input), Answer::Int(13));
+        assert_eq!(Day9::part2(&input), Answer::Int(1));
+    }
+
+    #[test]
+    fn larger_example() {
+        let input = Day9::parse("R 5\nU 8\nL 8\nD 3\nR 17\nD 10\nL 25\nU 20\n").unwrap();
+        assert_eq!(Day9::part2(&input), Answer::Int(36));
+    }
+
+    #[test]
+    fn tail_follows_head() {
+        let point = Point::new;
+        let tail = point(1, 1);
+
+        // Touching (including diagonally, or on top) means the tail stays put.
+        for head in [point(1, 1), point(2, 1), point(0, 0), point(2, 2)] {
+            assert_eq!(move_towards_point(tail, &head), tail);
+        }
+
+        // Two steps away in a straight line: follow along that line.
+        assert_eq!(move_towards_point(tail, &point(3, 1)), point(2, 1));
+        assert_eq!(move_towards_point(tail, &point(1, -1)), point(1, 0));
+
+        // Otherwise, move diagonally.
+        assert_eq!(move_towards_point(tail, &point(2, 3)), point(2, 2));
+        assert_eq!(move_towards_point(tail, &point(-1, 0)), point(0, 0));
+        assert_eq!(move_towards_point(tail, &point(3, 3)), point(2, 2));
+    }
+
+    #[test]
+    fn bad_direction() {
+        let error = Day9::parse("R 4\nX 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+    }
+}